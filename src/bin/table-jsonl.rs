@@ -0,0 +1,117 @@
+//! Dumps a table to JSON Lines or loads a dump back, e.g. for backups and seeding test tables.
+//!
+//! Items are read and written as plain JSON objects, so the table must use the default `PK`/`SK`
+//! string key schema. Binary attributes do not round-trip.
+
+use anyhow::{Context, anyhow, bail};
+use cloud_util::{DynamoDbClient, Keyed, Scan};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+
+const USAGE: &str = "Usage: table-jsonl export <table> [file] [--segments <n>]
+       table-jsonl import <table> [file]
+
+Reads from stdin or writes to stdout when no file is given.";
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct RawItem(Map<String, Value>);
+
+impl RawItem {
+    fn key(&self, attribute: &str) -> String {
+        match self.0.get(attribute) {
+            Some(Value::String(value)) => value.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Keyed for RawItem {
+    fn pk(&self) -> String {
+        self.key("PK")
+    }
+
+    fn sk(&self) -> String {
+        self.key("SK")
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut positional = Vec::new();
+    let mut segments = 1;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--segments" => {
+                segments = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--segments requires a value"))?
+                    .parse()
+                    .context("--segments must be a number")?;
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let (command, table_name, path) = match positional.as_slice() {
+        [command, table_name] => (*command, *table_name, None),
+        [command, table_name, path] => (*command, *table_name, Some(*path)),
+        _ => bail!("{USAGE}"),
+    };
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let client = DynamoDbClient::builder()
+            .table_name(table_name)
+            .build()
+            .await?;
+
+        match command {
+            "export" => {
+                let scan = Scan::new().segments(segments);
+                let count = match path {
+                    Some(path) => {
+                        let file =
+                            File::create(path).with_context(|| format!("Creating {path}"))?;
+                        client
+                            .export_entries::<RawItem, _>(&scan, BufWriter::new(file))
+                            .await?
+                    }
+                    None => {
+                        client
+                            .export_entries::<RawItem, _>(&scan, io::stdout().lock())
+                            .await?
+                    }
+                };
+                eprintln!("Exported {count} item(s) from {table_name}");
+            }
+            "import" => {
+                let count = match path {
+                    Some(path) => {
+                        let file = File::open(path).with_context(|| format!("Opening {path}"))?;
+                        client
+                            .import_entries::<RawItem, _>(BufReader::new(file))
+                            .await?
+                    }
+                    None => {
+                        client
+                            .import_entries::<RawItem, _>(io::stdin().lock())
+                            .await?
+                    }
+                };
+                eprintln!("Imported {count} item(s) into {table_name}");
+            }
+            _ => bail!("{USAGE}"),
+        }
+
+        Ok(())
+    })
+}
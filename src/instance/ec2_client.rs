@@ -1,13 +1,33 @@
 use crate::InstanceId;
-use crate::instance::InstanceMetadata;
+use crate::instance::launch_spec::LaunchTemplate;
+use crate::instance::{
+    InstanceFilter, InstanceMetadata, InstanceOutcome, InstanceState, LaunchSpec, WaitStrategy,
+};
 use anyhow::{Result, anyhow};
 use aws_sdk_ec2::Client;
 use aws_sdk_ec2::client::Waiters;
-use aws_sdk_ec2::types::{Filter, Instance};
+use aws_sdk_ec2::types::{
+    Filter, Instance, InstanceType, LaunchTemplateSpecification, ResourceType, SummaryStatus, Tag,
+    TagSpecification,
+};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::stream::{self, Stream, TryStreamExt};
 use std::collections::HashMap;
+use std::future::Future;
 use std::net::IpAddr;
-use std::time::Duration;
-use tracing::info;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const DESCRIBE_STATUS_SIZE: usize = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stop {
+    Normal,
+    Hibernate,
+    Force,
+}
 
 pub struct Ec2Client {
     client: Client,
@@ -18,24 +38,23 @@ impl Ec2Client {
         Ec2ClientBuilder::default()
     }
 
+    /// Streams every instance matching `filter`, fetching one page of results at a time.
+    pub fn stream_instances(
+        &self,
+        filter: &InstanceFilter,
+    ) -> impl Stream<Item = Result<InstanceMetadata>> + Send + '_ {
+        self.describe_instances(to_filters(filter))
+    }
+
     async fn find_instances_by_filter(
         &self,
         filters: Vec<Filter>,
     ) -> Result<Vec<InstanceMetadata>> {
-        let response = self
-            .client
-            .describe_instances()
-            .set_filters(Some(filters.clone()))
-            .send()
+        let instance_ids = self
+            .describe_instances(filters.clone())
+            .try_collect::<Vec<_>>()
             .await?;
 
-        let instance_ids = response
-            .reservations()
-            .iter()
-            .flat_map(|reservation| reservation.instances())
-            .map(|instance| instance.try_into())
-            .collect::<Result<Vec<InstanceMetadata>, _>>()?;
-
         info!(
             "Found \"{:?}\" with filters \"{:?}\"",
             instance_ids.clone(),
@@ -44,6 +63,235 @@ impl Ec2Client {
 
         Ok(instance_ids)
     }
+
+    fn describe_instances(
+        &self,
+        filters: Vec<Filter>,
+    ) -> impl Stream<Item = Result<InstanceMetadata>> + Send + '_ {
+        let request = self.client.describe_instances().set_filters(Some(filters));
+
+        // `None` once the last page was read, otherwise the token to continue from.
+        stream::try_unfold(Some(None::<String>), move |next_token| {
+            let request = request.clone();
+            async move {
+                let Some(next_token) = next_token else {
+                    return Ok(None);
+                };
+
+                let response = request.set_next_token(next_token).send().await?;
+                let instances = response
+                    .reservations()
+                    .iter()
+                    .flat_map(|reservation| reservation.instances())
+                    .map(|instance| instance.try_into())
+                    .collect::<Result<Vec<InstanceMetadata>>>()?;
+                let next_token = response
+                    .next_token
+                    .filter(|next_token| !next_token.is_empty());
+
+                Ok::<_, anyhow::Error>(Some((instances, next_token.map(Some))))
+            }
+        })
+        .map_ok(|instances| stream::iter(instances.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    async fn stop(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+        stop: Stop,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self
+            .request_each(instance_ids, |instance_ids| async move {
+                self.client
+                    .stop_instances()
+                    .set_instance_ids(Some(instance_ids))
+                    .set_hibernate((stop == Stop::Hibernate).then_some(true))
+                    .set_force((stop == Stop::Force).then_some(true))
+                    .send()
+                    .await?;
+                Ok(())
+            })
+            .await;
+
+        self.wait_for(outcomes, InstanceState::Stopped, wait).await
+    }
+
+    /// Sends `request` for the whole batch, falling back to one request per instance if the
+    /// batch is rejected so that one bad instance does not fail the others.
+    async fn request_each<F, Fut>(
+        &self,
+        instance_ids: &[InstanceId],
+        request: F,
+    ) -> HashMap<InstanceId, InstanceOutcome>
+    where
+        F: Fn(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let error = match request(instance_id_strings(instance_ids)).await {
+            Ok(()) => {
+                return instance_ids
+                    .iter()
+                    .map(|instance_id| (instance_id.clone(), InstanceOutcome::Requested))
+                    .collect();
+            }
+            Err(e) => e,
+        };
+
+        if let [instance_id] = instance_ids {
+            return HashMap::from([(
+                instance_id.clone(),
+                InstanceOutcome::Failed(format!("{error:#}")),
+            )]);
+        }
+
+        warn!("Batch request failed, retrying per instance: {error:#}");
+        let mut outcomes = HashMap::new();
+        for instance_id in instance_ids {
+            let outcome = match request(vec![instance_id.to_string()]).await {
+                Ok(()) => InstanceOutcome::Requested,
+                Err(e) => InstanceOutcome::Failed(format!("{e:#}")),
+            };
+            outcomes.insert(instance_id.clone(), outcome);
+        }
+        outcomes
+    }
+
+    /// Polls requested instances until each reaches `target`, fails, or the wait times out.
+    async fn wait_for(
+        &self,
+        mut outcomes: HashMap<InstanceId, InstanceOutcome>,
+        target: InstanceState,
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let (timeout, status_ok) = match wait {
+            WaitStrategy::NoWait => return Ok(outcomes),
+            WaitStrategy::State { timeout } => (timeout, false),
+            WaitStrategy::StatusOk { timeout } => (timeout, target == InstanceState::Running),
+        };
+        let deadline = Instant::now() + timeout;
+
+        let mut pending: HashMap<InstanceId, InstanceState> = outcomes
+            .iter()
+            .filter(|(_, outcome)| **outcome == InstanceOutcome::Requested)
+            .map(|(instance_id, _)| (instance_id.clone(), InstanceState::Unknown))
+            .collect();
+
+        while !pending.is_empty() {
+            let instance_ids = pending.keys().cloned().collect::<Vec<_>>();
+            match self.instance_statuses(&instance_ids).await {
+                Ok(statuses) => {
+                    for (instance_id, (state, checks_ok)) in statuses {
+                        let outcome = if state == target && (checks_ok || !status_ok) {
+                            InstanceOutcome::Ready
+                        } else if is_unrecoverable(state, target) {
+                            InstanceOutcome::Failed(format!(
+                                "Instance is {state:?} while waiting for {target:?}"
+                            ))
+                        } else {
+                            pending.insert(instance_id, state);
+                            continue;
+                        };
+                        pending.remove(&instance_id);
+                        outcomes.insert(instance_id, outcome);
+                    }
+                }
+                Err(e) => warn!("Failed to poll instance status, retrying: {e:#}"),
+            }
+
+            if pending.is_empty() {
+                break;
+            }
+            if Instant::now() + POLL_INTERVAL > deadline {
+                for (instance_id, state) in pending.drain() {
+                    outcomes.insert(instance_id, InstanceOutcome::TimedOut(state));
+                }
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Current state of each instance and whether its instance status checks pass.
+    async fn instance_statuses(
+        &self,
+        instance_ids: &[InstanceId],
+    ) -> Result<HashMap<InstanceId, (InstanceState, bool)>> {
+        let mut statuses = HashMap::new();
+
+        for chunk in instance_ids.chunks(DESCRIBE_STATUS_SIZE) {
+            let response = self
+                .client
+                .describe_instance_status()
+                .set_instance_ids(Some(instance_id_strings(chunk)))
+                .include_all_instances(true)
+                .send()
+                .await?;
+
+            for status in response.instance_statuses() {
+                let Some(instance_id) = status.instance_id() else {
+                    continue;
+                };
+                let state = status
+                    .instance_state()
+                    .and_then(|state| state.name())
+                    .map_or(InstanceState::Unknown, InstanceState::from);
+                let checks_ok = status
+                    .instance_status()
+                    .and_then(|summary| summary.status())
+                    == Some(&SummaryStatus::Ok);
+                statuses.insert(InstanceId::new(instance_id)?, (state, checks_ok));
+            }
+        }
+
+        Ok(statuses)
+    }
+}
+
+/// States an instance cannot leave on its own to reach `target`.
+fn is_unrecoverable(state: InstanceState, target: InstanceState) -> bool {
+    match target {
+        InstanceState::Running => matches!(
+            state,
+            InstanceState::Stopping | InstanceState::Terminating | InstanceState::Terminated
+        ),
+        InstanceState::Stopped => matches!(
+            state,
+            InstanceState::Starting | InstanceState::Terminating | InstanceState::Terminated
+        ),
+        _ => false,
+    }
+}
+
+fn count_succeeded(outcomes: &HashMap<InstanceId, InstanceOutcome>) -> usize {
+    outcomes
+        .values()
+        .filter(|outcome| matches!(outcome, InstanceOutcome::Requested | InstanceOutcome::Ready))
+        .count()
+}
+
+fn to_filters(filter: &InstanceFilter) -> Vec<Filter> {
+    filter
+        .conditions
+        .iter()
+        .map(|(name, values)| {
+            Filter::builder()
+                .name(name)
+                .set_values(Some(values.clone()))
+                .build()
+        })
+        .collect()
+}
+
+fn instance_id_strings(instance_ids: &[InstanceId]) -> Vec<String> {
+    instance_ids
+        .iter()
+        .map(AsRef::as_ref)
+        .map(str::to_string)
+        .collect()
 }
 
 #[async_trait::async_trait]
@@ -84,49 +332,175 @@ impl crate::instance::Instance for Ec2Client {
         self.find_instances_by_filter(filters).await
     }
 
-    async fn start_instances(&self, instance_ids: &[InstanceId]) -> Result<()> {
-        let instance_id_strings: Vec<String> = instance_ids
-            .iter()
-            .map(AsRef::as_ref)
-            .map(str::to_string)
-            .collect();
+    async fn find_instances(&self, filter: &InstanceFilter) -> Result<Vec<InstanceMetadata>> {
+        self.find_instances_by_filter(to_filters(filter)).await
+    }
 
-        self.client
-            .start_instances()
-            .set_instance_ids(Some(instance_id_strings.clone()))
+    async fn start_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self
+            .request_each(instance_ids, |instance_ids| async move {
+                self.client
+                    .start_instances()
+                    .set_instance_ids(Some(instance_ids))
+                    .send()
+                    .await?;
+                Ok(())
+            })
+            .await;
+
+        let outcomes = self
+            .wait_for(outcomes, InstanceState::Running, wait)
+            .await?;
+        info!("Started {} instance(s)", count_succeeded(&outcomes));
+        Ok(outcomes)
+    }
+
+    async fn stop_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self.stop(instance_ids, wait, Stop::Normal).await?;
+        info!("Stopped {} instance(s)", count_succeeded(&outcomes));
+        Ok(outcomes)
+    }
+
+    async fn hibernate_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self.stop(instance_ids, wait, Stop::Hibernate).await?;
+        info!("Hibernated {} instance(s)", count_succeeded(&outcomes));
+        Ok(outcomes)
+    }
+
+    async fn force_stop_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self.stop(instance_ids, wait, Stop::Force).await?;
+        info!("Force-stopped {} instance(s)", count_succeeded(&outcomes));
+        Ok(outcomes)
+    }
+
+    async fn reboot_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>> {
+        let outcomes = self
+            .request_each(instance_ids, |instance_ids| async move {
+                self.client
+                    .reboot_instances()
+                    .set_instance_ids(Some(instance_ids))
+                    .send()
+                    .await?;
+                Ok(())
+            })
+            .await;
+
+        let outcomes = self
+            .wait_for(outcomes, InstanceState::Running, wait)
+            .await?;
+        info!("Rebooted {} instance(s)", count_succeeded(&outcomes));
+        Ok(outcomes)
+    }
+
+    async fn launch_instances(&self, spec: &LaunchSpec) -> Result<Vec<InstanceMetadata>> {
+        let launch_template = spec.launch_template.as_ref().map(|template| {
+            let builder = match template {
+                LaunchTemplate::Id(id) => {
+                    LaunchTemplateSpecification::builder().launch_template_id(id)
+                }
+                LaunchTemplate::Name(name) => {
+                    LaunchTemplateSpecification::builder().launch_template_name(name)
+                }
+            };
+            builder
+                .set_version(spec.launch_template_version.clone())
+                .build()
+        });
+
+        let tag_specifications = (!spec.tags.is_empty()).then(|| {
+            let tags = spec
+                .tags
+                .iter()
+                .map(|(key, value)| Tag::builder().key(key).value(value).build())
+                .collect();
+            vec![
+                TagSpecification::builder()
+                    .resource_type(ResourceType::Instance)
+                    .set_tags(Some(tags))
+                    .build(),
+            ]
+        });
+
+        let response = self
+            .client
+            .run_instances()
+            .set_launch_template(launch_template)
+            .set_image_id(spec.image_id.clone())
+            .set_instance_type(spec.instance_type.as_deref().map(InstanceType::from))
+            .set_subnet_id(spec.subnet_id.clone())
+            .set_security_group_ids(
+                (!spec.security_group_ids.is_empty()).then(|| spec.security_group_ids.clone()),
+            )
+            .set_user_data(
+                spec.user_data
+                    .as_ref()
+                    .map(|user_data| BASE64.encode(user_data)),
+            )
+            .set_tag_specifications(tag_specifications)
+            .min_count(spec.count)
+            .max_count(spec.count)
             .send()
             .await?;
 
+        let instance_id_strings: Vec<String> = response
+            .instances()
+            .iter()
+            .filter_map(|instance| instance.instance_id())
+            .map(str::to_string)
+            .collect();
+
         self.client
-            .wait_until_instance_status_ok()
+            .wait_until_instance_running()
             .set_instance_ids(Some(instance_id_strings.clone()))
             .wait(Duration::from_secs(6000))
             .await?;
 
-        info!("Started {} instance(s)", instance_ids.len());
-        Ok(())
+        let filter = Filter::builder()
+            .name("instance-id")
+            .set_values(Some(instance_id_strings))
+            .build();
+        let instances = self.find_instances_by_filter(vec![filter]).await?;
+
+        info!("Launched {} instance(s)", instances.len());
+        Ok(instances)
     }
 
-    async fn stop_instances(&self, instance_ids: &[InstanceId]) -> Result<()> {
-        let instance_id_strings: Vec<String> = instance_ids
-            .iter()
-            .map(AsRef::as_ref)
-            .map(str::to_string)
-            .collect();
+    async fn terminate_instances(&self, instance_ids: &[InstanceId]) -> Result<()> {
+        let instance_id_strings = instance_id_strings(instance_ids);
 
         self.client
-            .stop_instances()
+            .terminate_instances()
             .set_instance_ids(Some(instance_id_strings.clone()))
             .send()
             .await?;
 
         self.client
-            .wait_until_instance_stopped()
-            .set_instance_ids(Some(instance_id_strings.clone()))
+            .wait_until_instance_terminated()
+            .set_instance_ids(Some(instance_id_strings))
             .wait(Duration::from_secs(6000))
             .await?;
 
-        info!("Stopped {} instance(s)", instance_ids.len());
+        info!("Terminated {} instance(s)", instance_ids.len());
         Ok(())
     }
 }
@@ -0,0 +1,79 @@
+use crate::instance::InstanceState;
+
+/// Conditions for `Instance::find_instances`.
+///
+/// Each call adds a condition that must hold; the values passed to one call are alternatives.
+/// Tag values may use `*` and `?` wildcards.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceFilter {
+    pub(crate) conditions: Vec<(String, Vec<String>)>,
+}
+
+impl InstanceFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(self, state: InstanceState) -> Self {
+        self.states(&[state])
+    }
+
+    /// `InstanceState::Unknown` matches nothing.
+    pub fn states(self, states: &[InstanceState]) -> Self {
+        let names = states
+            .iter()
+            .filter_map(|state| state_name(*state).map(str::to_owned))
+            .collect();
+        self.condition("instance-state-name", names)
+    }
+
+    pub fn instance_types(self, instance_types: &[&str]) -> Self {
+        self.condition("instance-type", owned(instance_types))
+    }
+
+    pub fn vpc_ids(self, vpc_ids: &[&str]) -> Self {
+        self.condition("vpc-id", owned(vpc_ids))
+    }
+
+    pub fn subnet_ids(self, subnet_ids: &[&str]) -> Self {
+        self.condition("subnet-id", owned(subnet_ids))
+    }
+
+    pub fn availability_zones(self, availability_zones: &[&str]) -> Self {
+        self.condition("availability-zone", owned(availability_zones))
+    }
+
+    pub fn tag(self, key: &str, value: &str) -> Self {
+        self.tag_values(key, &[value])
+    }
+
+    pub fn tag_values(self, key: &str, values: &[&str]) -> Self {
+        self.condition(&format!("tag:{key}"), owned(values))
+    }
+
+    /// Instances that have the tag, whatever its value.
+    pub fn has_tag(self, key: &str) -> Self {
+        self.condition("tag-key", vec![key.to_owned()])
+    }
+
+    fn condition(mut self, name: &str, values: Vec<String>) -> Self {
+        self.conditions.push((name.to_owned(), values));
+        self
+    }
+}
+
+fn owned(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| (*value).to_owned()).collect()
+}
+
+fn state_name(state: InstanceState) -> Option<&'static str> {
+    match state {
+        InstanceState::Starting => Some("pending"),
+        InstanceState::Running => Some("running"),
+        InstanceState::Stopping => Some("stopping"),
+        InstanceState::Stopped => Some("stopped"),
+        InstanceState::Terminating => Some("shutting-down"),
+        InstanceState::Terminated => Some("terminated"),
+        InstanceState::Unknown => None,
+    }
+}
@@ -2,6 +2,7 @@ use crate::InstanceId;
 use aws_sdk_ec2::types::InstanceStateName;
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InstanceState {
@@ -35,3 +36,28 @@ pub struct InstanceMetadata {
     pub status: InstanceState,
     pub tags: HashMap<String, String>,
 }
+
+/// How long state changes such as `Instance::start_instances` block once EC2 accepts them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStrategy {
+    /// Return as soon as the request is accepted.
+    NoWait,
+    /// Wait until each instance reaches the target state, e.g. `running` or `stopped`.
+    State { timeout: Duration },
+    /// Like `State`, but started instances must also pass their status checks.
+    StatusOk { timeout: Duration },
+}
+
+/// What happened to one instance of a batch state change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstanceOutcome {
+    /// The request was accepted and `WaitStrategy::NoWait` was given.
+    Requested,
+    /// The instance reached the state waited for.
+    Ready,
+    /// The wait ended first; holds the last state seen.
+    TimedOut(InstanceState),
+    /// The request was rejected for this instance, or it moved to a state it cannot recover
+    /// from, e.g. terminated while starting.
+    Failed(String),
+}
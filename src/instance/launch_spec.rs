@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LaunchTemplate {
+    Id(String),
+    Name(String),
+}
+
+/// What `Instance::launch_instances` starts; explicit settings override the launch template's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchSpec {
+    pub(crate) launch_template: Option<LaunchTemplate>,
+    pub(crate) launch_template_version: Option<String>,
+    pub(crate) image_id: Option<String>,
+    pub(crate) instance_type: Option<String>,
+    pub(crate) subnet_id: Option<String>,
+    pub(crate) security_group_ids: Vec<String>,
+    pub(crate) user_data: Option<String>,
+    pub(crate) tags: HashMap<String, String>,
+    pub(crate) count: i32,
+}
+
+impl LaunchSpec {
+    pub fn new(image_id: &str, instance_type: &str) -> Self {
+        Self {
+            image_id: Some(image_id.to_owned()),
+            instance_type: Some(instance_type.to_owned()),
+            ..Self::empty()
+        }
+    }
+
+    pub fn from_template_id(launch_template_id: &str) -> Self {
+        Self {
+            launch_template: Some(LaunchTemplate::Id(launch_template_id.to_owned())),
+            ..Self::empty()
+        }
+    }
+
+    pub fn from_template_name(launch_template_name: &str) -> Self {
+        Self {
+            launch_template: Some(LaunchTemplate::Name(launch_template_name.to_owned())),
+            ..Self::empty()
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            launch_template: None,
+            launch_template_version: None,
+            image_id: None,
+            instance_type: None,
+            subnet_id: None,
+            security_group_ids: Vec::new(),
+            user_data: None,
+            tags: HashMap::new(),
+            count: 1,
+        }
+    }
+
+    /// Launch template version, e.g. `$Latest`; the template's default version otherwise.
+    pub fn template_version(mut self, version: &str) -> Self {
+        self.launch_template_version = Some(version.to_owned());
+        self
+    }
+
+    pub fn image_id(mut self, image_id: &str) -> Self {
+        self.image_id = Some(image_id.to_owned());
+        self
+    }
+
+    pub fn instance_type(mut self, instance_type: &str) -> Self {
+        self.instance_type = Some(instance_type.to_owned());
+        self
+    }
+
+    pub fn subnet_id(mut self, subnet_id: &str) -> Self {
+        self.subnet_id = Some(subnet_id.to_owned());
+        self
+    }
+
+    pub fn security_group_id(mut self, security_group_id: &str) -> Self {
+        self.security_group_ids.push(security_group_id.to_owned());
+        self
+    }
+
+    /// Plain-text user data; it is base64-encoded on launch.
+    pub fn user_data(mut self, user_data: &str) -> Self {
+        self.user_data = Some(user_data.to_owned());
+        self
+    }
+
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Number of instances to launch, 1 by default; all or none are launched.
+    pub fn count(mut self, count: i32) -> Self {
+        self.count = count;
+        self
+    }
+}
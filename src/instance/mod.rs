@@ -2,12 +2,16 @@ use anyhow::Result;
 use std::collections::HashMap;
 
 mod ec2_client;
+mod instance_filter;
 mod instance_id;
 mod instance_model;
+mod launch_spec;
 
 pub use ec2_client::Ec2Client;
+pub use instance_filter::InstanceFilter;
 pub use instance_id::InstanceId;
-pub use instance_model::{InstanceMetadata, InstanceState};
+pub use instance_model::{InstanceMetadata, InstanceOutcome, InstanceState, WaitStrategy};
+pub use launch_spec::LaunchSpec;
 
 #[async_trait::async_trait]
 pub trait Instance: Send + Sync {
@@ -19,6 +23,42 @@ pub trait Instance: Send + Sync {
         &self,
         tags: &HashMap<String, String>,
     ) -> Result<Vec<InstanceMetadata>>;
-    async fn start_instances(&self, instance_ids: &[InstanceId]) -> Result<()>;
-    async fn stop_instances(&self, instance_ids: &[InstanceId]) -> Result<()>;
+    async fn find_instances(&self, filter: &InstanceFilter) -> Result<Vec<InstanceMetadata>>;
+    async fn start_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>>;
+    async fn stop_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>>;
+
+    /// Stops instances saving their memory to disk; fails for instances not enabled for
+    /// hibernation.
+    async fn hibernate_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>>;
+
+    /// Stops instances without a graceful shutdown, e.g. when they are stuck stopping.
+    async fn force_stop_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>>;
+
+    /// Instances stay running through a reboot, so waiting only confirms they are running
+    /// and, with `WaitStrategy::StatusOk`, passing status checks.
+    async fn reboot_instances(
+        &self,
+        instance_ids: &[InstanceId],
+        wait: WaitStrategy,
+    ) -> Result<HashMap<InstanceId, InstanceOutcome>>;
+
+    /// Launches instances and returns them once they are running.
+    async fn launch_instances(&self, spec: &LaunchSpec) -> Result<Vec<InstanceMetadata>>;
+    async fn terminate_instances(&self, instance_ids: &[InstanceId]) -> Result<()>;
 }
@@ -3,7 +3,10 @@ mod helper;
 #[cfg(feature = "instance")]
 mod instance;
 #[cfg(feature = "instance")]
-pub use instance::{Ec2Client, Instance, InstanceId, InstanceMetadata, InstanceState};
+pub use instance::{
+    Ec2Client, Instance, InstanceFilter, InstanceId, InstanceMetadata, InstanceOutcome,
+    InstanceState, LaunchSpec, WaitStrategy,
+};
 
 #[cfg(feature = "manager")]
 mod manager;
@@ -22,8 +25,22 @@ pub use secretsmanager::SecretsManager;
 
 #[cfg(feature = "table")]
 mod table;
+#[cfg(feature = "in-memory-table")]
+pub use table::InMemoryTable;
 #[cfg(feature = "table")]
-pub use table::{DynamoDbClient, Table};
+pub use table::{
+    BillingMode, CachedTable, CachedTableBuilder, Condition, Cursor, DistributedLock,
+    DistributedLockBuilder, DynamoDbClient, EntitySet, Index, IntoAttribute, KeyAttribute,
+    KeySchema, KeyType, LockGuard, LockLease, Page, Query, RawEntity, Scan, SchemaDifference,
+    SortKeyCondition, Table, TableAdmin, TableError, TrackingAttributes, Transaction,
+    TransactionFailure, Update, UpdateAction,
+};
+#[cfg(feature = "table-stream")]
+pub use table::{ChangeEvent, ShardCheckpoint, StreamConsumer, StreamEvent};
+#[cfg(feature = "table-encryption")]
+pub use table::{DataKey, KeyProvider, LocalKeyProvider};
+#[cfg(feature = "table-overflow")]
+pub use table::{InMemoryObjectStore, LocalObjectStore, ObjectStore};
 
 #[cfg(feature = "metadata")]
 mod metadata;
@@ -43,4 +60,4 @@ pub use config_store::{SyncSsmParameterClient, get_config};
 #[cfg(feature = "model")]
 mod model;
 #[cfg(feature = "model")]
-pub use model::Keyed;
+pub use model::{Keyed, Tracked};
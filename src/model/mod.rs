@@ -1,4 +1,42 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 pub trait Keyed {
     fn pk(&self) -> String;
     fn sk(&self) -> String;
+
+    /// Secondary index key attributes (e.g. `GSI1PK`, `GSI1SK`) written alongside the primary key.
+    fn index_keys(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Tag that distinguishes this type from others sharing a table; reads skip foreign items.
+    fn entity_type() -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Attributes encrypted client-side on write and decrypted on read; must not be key attributes.
+    fn encrypted_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+}
+
+/// Opt-in record lifecycle handled on write: timestamps, expiry and optimistic versioning.
+pub trait Tracked: Keyed {
+    /// Version the record had when it was read; `0` for a record that was never written.
+    fn version(&self) -> u64;
+
+    /// How long after each write the record expires.
+    fn time_to_live() -> Option<Duration>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
@@ -0,0 +1,459 @@
+use crate::table::schema::{Index, KeyAttribute, KeySchema, KeyType};
+use anyhow::{Result, anyhow, bail};
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::client::Waiters;
+use aws_sdk_dynamodb::operation::describe_table::DescribeTableError;
+use aws_sdk_dynamodb::types::{
+    self, AttributeDefinition, GlobalSecondaryIndex, KeySchemaElement, Projection, ProjectionType,
+    ProvisionedThroughput, ScalarAttributeType, TableDescription, TimeToLiveSpecification,
+    TimeToLiveStatus,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tracing::info;
+
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BillingMode {
+    PayPerRequest,
+    /// Capacity units applied to the table and each of its indexes.
+    Provisioned {
+        read_capacity: i64,
+        write_capacity: i64,
+    },
+}
+
+/// A way in which a live table differs from its declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaDifference {
+    KeySchema {
+        expected: KeySchema,
+        actual: KeySchema,
+    },
+    MissingIndex(String),
+    UndeclaredIndex(String),
+    IndexKeySchema {
+        index: String,
+        expected: KeySchema,
+        actual: KeySchema,
+    },
+    BillingMode {
+        expected: BillingMode,
+        actual: BillingMode,
+    },
+    TimeToLive {
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+}
+
+/// Creates a table from a schema declared in code and compares existing tables against it.
+///
+/// Every `Index` is created as a global secondary index projecting all attributes.
+pub struct TableAdmin {
+    client: Client,
+    table_name: String,
+    key_schema: KeySchema,
+    indexes: HashMap<String, Index>,
+    billing_mode: BillingMode,
+    time_to_live_attribute: Option<String>,
+    wait_timeout: Duration,
+}
+
+impl TableAdmin {
+    pub fn builder() -> TableAdminBuilder {
+        TableAdminBuilder::default()
+    }
+
+    /// Creates the table if it is missing; an existing table is left as is and diffed instead.
+    pub async fn ensure_table(&self) -> Result<Vec<SchemaDifference>> {
+        if self.describe_table().await?.is_some() {
+            return self.diff().await;
+        }
+
+        self.create_table().await?;
+        Ok(Vec::new())
+    }
+
+    /// Creates the table, waits for it to become active and enables TTL if declared.
+    pub async fn create_table(&self) -> Result<()> {
+        let indexes = self
+            .indexes
+            .values()
+            .map(|index| self.global_secondary_index(index))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.client
+            .create_table()
+            .table_name(&self.table_name)
+            .set_key_schema(Some(key_schema_elements(&self.key_schema)?))
+            .set_attribute_definitions(Some(self.attribute_definitions()?))
+            .set_global_secondary_indexes((!indexes.is_empty()).then_some(indexes))
+            .billing_mode(match self.billing_mode {
+                BillingMode::PayPerRequest => types::BillingMode::PayPerRequest,
+                BillingMode::Provisioned { .. } => types::BillingMode::Provisioned,
+            })
+            .set_provisioned_throughput(self.provisioned_throughput()?)
+            .send()
+            .await?;
+        info!("Created table {}", self.table_name);
+
+        self.wait_until_active().await?;
+
+        if let Some(attribute) = &self.time_to_live_attribute {
+            let specification = TimeToLiveSpecification::builder()
+                .enabled(true)
+                .attribute_name(attribute)
+                .build()?;
+            self.client
+                .update_time_to_live()
+                .table_name(&self.table_name)
+                .time_to_live_specification(specification)
+                .send()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn wait_until_active(&self) -> Result<()> {
+        self.client
+            .wait_until_table_exists()
+            .table_name(&self.table_name)
+            .wait(self.wait_timeout)
+            .await?;
+        Ok(())
+    }
+
+    /// Compares the live table with the declaration; empty when they match.
+    pub async fn diff(&self) -> Result<Vec<SchemaDifference>> {
+        let table = self
+            .describe_table()
+            .await?
+            .ok_or_else(|| anyhow!("Table {} does not exist", self.table_name))?;
+
+        let key_types: HashMap<&str, KeyType> = table
+            .attribute_definitions()
+            .iter()
+            .filter_map(|definition| {
+                Some((
+                    definition.attribute_name(),
+                    key_type(definition.attribute_type())?,
+                ))
+            })
+            .collect();
+
+        let mut differences = Vec::new();
+
+        let actual = key_schema(table.key_schema(), &key_types)?;
+        if actual != self.key_schema {
+            differences.push(SchemaDifference::KeySchema {
+                expected: self.key_schema.clone(),
+                actual,
+            });
+        }
+
+        let global_indexes = table
+            .global_secondary_indexes()
+            .iter()
+            .map(|index| (index.index_name(), index.key_schema()));
+        let local_indexes = table
+            .local_secondary_indexes()
+            .iter()
+            .map(|index| (index.index_name(), index.key_schema()));
+        let mut actual_indexes = global_indexes
+            .chain(local_indexes)
+            .filter_map(|(name, elements)| Some((name?.to_owned(), elements)))
+            .map(|(name, elements)| Ok((name, key_schema(elements, &key_types)?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        let declared: BTreeMap<_, _> = self.indexes.iter().collect();
+        for (name, index) in declared {
+            match actual_indexes.remove(name) {
+                None => differences.push(SchemaDifference::MissingIndex(name.clone())),
+                Some(actual) if actual != index.key_schema => {
+                    differences.push(SchemaDifference::IndexKeySchema {
+                        index: name.clone(),
+                        expected: index.key_schema.clone(),
+                        actual,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        differences.extend(
+            actual_indexes
+                .into_keys()
+                .map(SchemaDifference::UndeclaredIndex),
+        );
+
+        let actual = billing_mode(&table);
+        if actual != self.billing_mode {
+            differences.push(SchemaDifference::BillingMode {
+                expected: self.billing_mode,
+                actual,
+            });
+        }
+
+        let actual = self.time_to_live_attribute().await?;
+        if actual != self.time_to_live_attribute {
+            differences.push(SchemaDifference::TimeToLive {
+                expected: self.time_to_live_attribute.clone(),
+                actual,
+            });
+        }
+
+        Ok(differences)
+    }
+
+    async fn describe_table(&self) -> Result<Option<TableDescription>> {
+        match self
+            .client
+            .describe_table()
+            .table_name(&self.table_name)
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.table),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(DescribeTableError::is_resource_not_found_exception) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn time_to_live_attribute(&self) -> Result<Option<String>> {
+        let resp = self
+            .client
+            .describe_time_to_live()
+            .table_name(&self.table_name)
+            .send()
+            .await?;
+
+        Ok(resp.time_to_live_description.and_then(|description| {
+            match description.time_to_live_status {
+                Some(TimeToLiveStatus::Enabled | TimeToLiveStatus::Enabling) => {
+                    description.attribute_name
+                }
+                _ => None,
+            }
+        }))
+    }
+
+    /// One definition per key attribute across the table and its indexes.
+    fn attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        let mut attributes: BTreeMap<&str, &KeyAttribute> = BTreeMap::new();
+        let key_schemas = std::iter::once(&self.key_schema)
+            .chain(self.indexes.values().map(|index| &index.key_schema));
+
+        for key_schema in key_schemas {
+            for key in std::iter::once(&key_schema.partition_key).chain(&key_schema.sort_key) {
+                match attributes.insert(&key.name, key) {
+                    Some(existing) if existing.key_type != key.key_type => {
+                        bail!("Attribute {} is declared with conflicting types", key.name)
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        attributes
+            .into_values()
+            .map(|key| {
+                let attribute_type = match key.key_type {
+                    KeyType::String => ScalarAttributeType::S,
+                    KeyType::Number => ScalarAttributeType::N,
+                    KeyType::Binary => ScalarAttributeType::B,
+                };
+                Ok(AttributeDefinition::builder()
+                    .attribute_name(&key.name)
+                    .attribute_type(attribute_type)
+                    .build()?)
+            })
+            .collect()
+    }
+
+    fn global_secondary_index(&self, index: &Index) -> Result<GlobalSecondaryIndex> {
+        Ok(GlobalSecondaryIndex::builder()
+            .index_name(&index.name)
+            .set_key_schema(Some(key_schema_elements(&index.key_schema)?))
+            .projection(
+                Projection::builder()
+                    .projection_type(ProjectionType::All)
+                    .build(),
+            )
+            .set_provisioned_throughput(self.provisioned_throughput()?)
+            .build()?)
+    }
+
+    fn provisioned_throughput(&self) -> Result<Option<ProvisionedThroughput>> {
+        let BillingMode::Provisioned {
+            read_capacity,
+            write_capacity,
+        } = self.billing_mode
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            ProvisionedThroughput::builder()
+                .read_capacity_units(read_capacity)
+                .write_capacity_units(write_capacity)
+                .build()?,
+        ))
+    }
+}
+
+fn key_schema_elements(key_schema: &KeySchema) -> Result<Vec<KeySchemaElement>> {
+    std::iter::once((&key_schema.partition_key, types::KeyType::Hash))
+        .chain(
+            key_schema
+                .sort_key
+                .as_ref()
+                .map(|key| (key, types::KeyType::Range)),
+        )
+        .map(|(key, key_type)| {
+            Ok(KeySchemaElement::builder()
+                .attribute_name(&key.name)
+                .key_type(key_type)
+                .build()?)
+        })
+        .collect()
+}
+
+fn key_schema(
+    elements: &[KeySchemaElement],
+    key_types: &HashMap<&str, KeyType>,
+) -> Result<KeySchema> {
+    let key_attribute = |key_type: types::KeyType| {
+        elements
+            .iter()
+            .find(|element| *element.key_type() == key_type)
+            .map(|element| {
+                let name = element.attribute_name();
+                let key_type = key_types
+                    .get(name)
+                    .ok_or_else(|| anyhow!("No attribute definition for key {}", name))?;
+                Ok::<_, anyhow::Error>(KeyAttribute::new(name, *key_type))
+            })
+            .transpose()
+    };
+
+    let partition_key = key_attribute(types::KeyType::Hash)?
+        .ok_or_else(|| anyhow!("Key schema has no partition key"))?;
+    let sort_key = key_attribute(types::KeyType::Range)?;
+
+    Ok(KeySchema {
+        partition_key,
+        sort_key,
+    })
+}
+
+fn key_type(attribute_type: &ScalarAttributeType) -> Option<KeyType> {
+    match attribute_type {
+        ScalarAttributeType::S => Some(KeyType::String),
+        ScalarAttributeType::N => Some(KeyType::Number),
+        ScalarAttributeType::B => Some(KeyType::Binary),
+        _ => None,
+    }
+}
+
+/// Tables created before billing mode summaries existed report none and are provisioned.
+fn billing_mode(table: &TableDescription) -> BillingMode {
+    let billing_mode = table
+        .billing_mode_summary()
+        .and_then(|summary| summary.billing_mode());
+
+    match billing_mode {
+        Some(types::BillingMode::PayPerRequest) => BillingMode::PayPerRequest,
+        _ => {
+            let throughput = table.provisioned_throughput();
+            BillingMode::Provisioned {
+                read_capacity: throughput
+                    .and_then(|throughput| throughput.read_capacity_units())
+                    .unwrap_or_default(),
+                write_capacity: throughput
+                    .and_then(|throughput| throughput.write_capacity_units())
+                    .unwrap_or_default(),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TableAdminBuilder {
+    client: Option<Client>,
+    table_name: Option<String>,
+    key_schema: Option<KeySchema>,
+    indexes: HashMap<String, Index>,
+    billing_mode: Option<BillingMode>,
+    time_to_live_attribute: Option<String>,
+    wait_timeout: Option<Duration>,
+}
+
+impl TableAdminBuilder {
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn table_name(mut self, table_name: &str) -> Self {
+        self.table_name = Some(table_name.to_owned());
+        self
+    }
+
+    /// Defaults to string `PK` and `SK` attributes.
+    pub fn key_schema(mut self, key_schema: KeySchema) -> Self {
+        self.key_schema = Some(key_schema);
+        self
+    }
+
+    pub fn index(mut self, index: Index) -> Self {
+        self.indexes.insert(index.name.clone(), index);
+        self
+    }
+
+    /// Defaults to `BillingMode::PayPerRequest`.
+    pub fn billing_mode(mut self, billing_mode: BillingMode) -> Self {
+        self.billing_mode = Some(billing_mode);
+        self
+    }
+
+    /// Usually `TrackingAttributes::expires_at`; TTL stays disabled when unset.
+    pub fn time_to_live_attribute(mut self, attribute: &str) -> Self {
+        self.time_to_live_attribute = Some(attribute.to_owned());
+        self
+    }
+
+    /// How long to wait for a new table to become active, five minutes by default.
+    pub fn wait_timeout(mut self, wait_timeout: Duration) -> Self {
+        self.wait_timeout = Some(wait_timeout);
+        self
+    }
+
+    pub async fn build(self) -> Result<TableAdmin> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let config = aws_config::load_from_env().await;
+                Client::new(&config)
+            }
+        };
+
+        let table_name = self.table_name.ok_or(anyhow!("Missing table name"))?;
+
+        Ok(TableAdmin {
+            client,
+            table_name,
+            key_schema: self.key_schema.unwrap_or_default(),
+            indexes: self.indexes,
+            billing_mode: self.billing_mode.unwrap_or(BillingMode::PayPerRequest),
+            time_to_live_attribute: self.time_to_live_attribute,
+            wait_timeout: self.wait_timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT),
+        })
+    }
+}
@@ -0,0 +1,274 @@
+use crate::Table;
+use crate::table::Keyed;
+use crate::table::error::{Result, TableError};
+use crate::table::expression::{Condition, Update};
+use crate::table::query::{Page, Query};
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_CAPACITY: usize = 1024;
+
+type Key = (String, String);
+
+struct CacheEntry<T> {
+    /// `None` records that the item was not found.
+    item: Option<T>,
+    expires_at: Instant,
+}
+
+/// Read-through cache for `get_entry` in front of any `Table`.
+///
+/// Writes through this wrapper invalidate the affected entries; writes made elsewhere are only
+/// picked up once entries expire.
+pub struct CachedTable<T, S> {
+    inner: S,
+    entries: Mutex<HashMap<Key, CacheEntry<T>>>,
+    ttl: Duration,
+    negative_ttl: Option<Duration>,
+    capacity: usize,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T, S> CachedTable<T, S>
+where
+    T: Serialize + DeserializeOwned + Keyed + Clone + Send + Sync,
+    S: Table<T>,
+{
+    pub fn builder(inner: S) -> CachedTableBuilder<T, S> {
+        CachedTableBuilder {
+            inner,
+            ttl: None,
+            negative_ttl: None,
+            capacity: None,
+            _entry: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn invalidate(&self, pk: &str, sk: &str) -> Result<()> {
+        self.entries()?.remove(&(pk.to_owned(), sk.to_owned()));
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        self.entries()?.clear();
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<MutexGuard<'_, HashMap<Key, CacheEntry<T>>>> {
+        self.entries
+            .lock()
+            .map_err(|_| TableError::Service("Table cache lock poisoned".into()))
+    }
+
+    /// Returns `Some(None)` for a cached miss and `None` when the key is not cached.
+    fn cached(&self, pk: &str, sk: &str) -> Result<Option<Option<T>>> {
+        let key = (pk.to_owned(), sk.to_owned());
+        let mut entries = self.entries()?;
+
+        match entries.get(&key) {
+            Some(entry) if entry.expires_at > Instant::now() => Ok(Some(entry.item.clone())),
+            Some(_) => {
+                entries.remove(&key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn store(&self, pk: &str, sk: &str, item: Option<T>) -> Result<()> {
+        let Some(ttl) = (if item.is_some() {
+            Some(self.ttl)
+        } else {
+            self.negative_ttl
+        }) else {
+            return Ok(());
+        };
+
+        let key = (pk.to_owned(), sk.to_owned());
+        let mut entries = self.entries()?;
+
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires_at > now);
+
+            // Evict the entry closest to expiry, which is the oldest one under a single TTL.
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+
+        if self.capacity > 0 {
+            let expires_at = Instant::now() + ttl;
+            entries.insert(key, CacheEntry { item, expires_at });
+        }
+        Ok(())
+    }
+
+    /// Drops the entry whatever the outcome of the write, since a failed write may still have
+    /// been applied.
+    fn invalidate_after<R>(&self, pk: &str, sk: &str, result: Result<R>) -> Result<R> {
+        self.invalidate(pk, sk)?;
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, S> Table<T> for CachedTable<T, S>
+where
+    T: Serialize + DeserializeOwned + Keyed + Clone + Send + Sync + 'static,
+    S: Table<T>,
+{
+    async fn get_entry(&self, pk: &str, sk: &str) -> Result<T> {
+        let not_found = || TableError::NotFound {
+            pk: pk.to_owned(),
+            sk: sk.to_owned(),
+        };
+
+        if let Some(cached) = self.cached(pk, sk)? {
+            return cached.ok_or_else(not_found);
+        }
+
+        match self.inner.get_entry(pk, sk).await {
+            Ok(item) => {
+                self.store(pk, sk, Some(item.clone()))?;
+                Ok(item)
+            }
+            Err(TableError::NotFound { .. }) => {
+                self.store(pk, sk, None)?;
+                Err(not_found())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Projections are not cached.
+    async fn find_projection<P>(&self, pk: &str, sk: &str, attributes: &[&str]) -> Result<Option<P>>
+    where
+        P: DeserializeOwned + Send,
+    {
+        self.inner.find_projection(pk, sk, attributes).await
+    }
+
+    async fn put_entry(&self, item: T) -> Result<()> {
+        let (pk, sk) = (item.pk(), item.sk());
+        let result = self.inner.put_entry(item).await;
+        self.invalidate_after(&pk, &sk, result)
+    }
+
+    async fn put_entry_if(&self, item: T, condition: &Condition) -> Result<()> {
+        let (pk, sk) = (item.pk(), item.sk());
+        let result = self.inner.put_entry_if(item, condition).await;
+        self.invalidate_after(&pk, &sk, result)
+    }
+
+    async fn delete_entry(&self, pk: &str, sk: &str) -> Result<()> {
+        let result = self.inner.delete_entry(pk, sk).await;
+        self.invalidate_after(pk, sk, result)
+    }
+
+    async fn delete_entry_if(&self, pk: &str, sk: &str, condition: &Condition) -> Result<()> {
+        let result = self.inner.delete_entry_if(pk, sk, condition).await;
+        self.invalidate_after(pk, sk, result)
+    }
+
+    async fn update_entry(&self, pk: &str, sk: &str, update: &Update) -> Result<T> {
+        let result = self.inner.update_entry(pk, sk, update).await;
+        self.invalidate_after(pk, sk, result)
+    }
+
+    async fn update_attribute(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        attribute: &str,
+    ) -> Result<Option<AttributeValue>> {
+        let result = self.inner.update_attribute(pk, sk, update, attribute).await;
+        self.invalidate_after(pk, sk, result)
+    }
+
+    /// Always reads from the inner table, refreshing cached entries for the items returned.
+    async fn batch_get_entries(&self, keys: &[(&str, &str)]) -> Result<Vec<T>> {
+        let items = self.inner.batch_get_entries(keys).await?;
+        for item in &items {
+            self.store(&item.pk(), &item.sk(), Some(item.clone()))?;
+        }
+        Ok(items)
+    }
+
+    async fn batch_put_entries(&self, items: Vec<T>) -> Result<()> {
+        let keys = items
+            .iter()
+            .map(|item| (item.pk(), item.sk()))
+            .collect::<Vec<_>>();
+        let result = self.inner.batch_put_entries(items).await;
+
+        for (pk, sk) in &keys {
+            self.invalidate(pk, sk)?;
+        }
+        result
+    }
+
+    /// Queries are not cached.
+    async fn query(&self, query: &Query) -> Result<Page<T>> {
+        self.inner.query(query).await
+    }
+}
+
+pub struct CachedTableBuilder<T, S> {
+    inner: S,
+    ttl: Option<Duration>,
+    negative_ttl: Option<Duration>,
+    capacity: Option<usize>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T, S> CachedTableBuilder<T, S>
+where
+    T: Serialize + DeserializeOwned + Keyed + Clone + Send + Sync,
+    S: Table<T>,
+{
+    /// How long found items are served from the cache, 60 seconds by default.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Caches `NotFound` results for this long; misses are not cached unless set.
+    pub fn negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = Some(negative_ttl);
+        self
+    }
+
+    /// Maximum number of cached entries, 1024 by default.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn build(self) -> CachedTable<T, S> {
+        CachedTable {
+            inner: self.inner,
+            entries: Mutex::default(),
+            ttl: self.ttl.unwrap_or(DEFAULT_TTL),
+            negative_ttl: self.negative_ttl,
+            capacity: self.capacity.unwrap_or(DEFAULT_CAPACITY),
+            _entry: PhantomData,
+        }
+    }
+}
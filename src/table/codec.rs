@@ -0,0 +1,169 @@
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+use std::fmt;
+
+/// An attribute value that cannot be encoded, or bytes that are not a valid encoding.
+#[derive(Debug)]
+pub(crate) struct CodecError(&'static str);
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+type Result<T> = std::result::Result<T, CodecError>;
+
+const MALFORMED: CodecError = CodecError("Malformed encoded attribute value");
+
+// Attribute values are encoded as a tag byte followed by length-prefixed bytes for scalars, or
+// an element count and the elements for collections.
+const S: u8 = 0;
+const N: u8 = 1;
+const B: u8 = 2;
+const BOOL: u8 = 3;
+const NULL: u8 = 4;
+const M: u8 = 5;
+const L: u8 = 6;
+const SS: u8 = 7;
+const NS: u8 = 8;
+const BS: u8 = 9;
+
+pub(crate) fn encode(value: &AttributeValue, out: &mut Vec<u8>) -> Result<()> {
+    let write_len = |out: &mut Vec<u8>, len: usize| out.extend((len as u32).to_be_bytes());
+    let write_bytes = |out: &mut Vec<u8>, bytes: &[u8]| {
+        write_len(out, bytes.len());
+        out.extend(bytes);
+    };
+
+    match value {
+        AttributeValue::S(value) => {
+            out.push(S);
+            write_bytes(out, value.as_bytes());
+        }
+        AttributeValue::N(value) => {
+            out.push(N);
+            write_bytes(out, value.as_bytes());
+        }
+        AttributeValue::B(value) => {
+            out.push(B);
+            write_bytes(out, value.as_ref());
+        }
+        AttributeValue::Bool(value) => out.extend([BOOL, *value as u8]),
+        AttributeValue::Null(_) => out.push(NULL),
+        AttributeValue::M(map) => {
+            out.push(M);
+            write_len(out, map.len());
+            for (name, value) in map {
+                write_bytes(out, name.as_bytes());
+                encode(value, out)?;
+            }
+        }
+        AttributeValue::L(list) => {
+            out.push(L);
+            write_len(out, list.len());
+            for value in list {
+                encode(value, out)?;
+            }
+        }
+        AttributeValue::Ss(values) | AttributeValue::Ns(values) => {
+            out.push(if matches!(value, AttributeValue::Ss(_)) {
+                SS
+            } else {
+                NS
+            });
+            write_len(out, values.len());
+            for value in values {
+                write_bytes(out, value.as_bytes());
+            }
+        }
+        AttributeValue::Bs(values) => {
+            out.push(BS);
+            write_len(out, values.len());
+            for value in values {
+                write_bytes(out, value.as_ref());
+            }
+        }
+        _ => {
+            return Err(CodecError("Unsupported attribute value type"));
+        }
+    }
+
+    Ok(())
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(MALFORMED);
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| MALFORMED)
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<AttributeValue> {
+    decode_value(&mut Reader(bytes))
+}
+
+fn decode_value(reader: &mut Reader) -> Result<AttributeValue> {
+    let value = match reader.take(1)?[0] {
+        S => AttributeValue::S(reader.string()?),
+        N => AttributeValue::N(reader.string()?),
+        B => AttributeValue::B(Blob::new(reader.bytes()?)),
+        BOOL => AttributeValue::Bool(reader.take(1)?[0] != 0),
+        NULL => AttributeValue::Null(true),
+        M => {
+            let len = reader.len()?;
+            let mut map = HashMap::with_capacity(len);
+            for _ in 0..len {
+                let name = reader.string()?;
+                map.insert(name, decode_value(reader)?);
+            }
+            AttributeValue::M(map)
+        }
+        L => {
+            let len = reader.len()?;
+            let list = (0..len)
+                .map(|_| decode_value(reader))
+                .collect::<Result<Vec<_>>>()?;
+            AttributeValue::L(list)
+        }
+        SS => AttributeValue::Ss(strings(reader)?),
+        NS => AttributeValue::Ns(strings(reader)?),
+        BS => {
+            let len = reader.len()?;
+            let values = (0..len)
+                .map(|_| Ok(Blob::new(reader.bytes()?)))
+                .collect::<Result<Vec<_>>>()?;
+            AttributeValue::Bs(values)
+        }
+        _ => return Err(MALFORMED),
+    };
+    Ok(value)
+}
+
+fn strings(reader: &mut Reader) -> Result<Vec<String>> {
+    let len = reader.len()?;
+    (0..len).map(|_| reader.string()).collect()
+}
@@ -0,0 +1,133 @@
+use super::{DynamoDbClient, Item};
+use crate::table::error::{Result, TableError};
+use aws_sdk_dynamodb::types::{KeysAndAttributes, PutRequest, WriteRequest};
+use futures::{StreamExt, TryStreamExt, stream};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const BATCH_GET_SIZE: usize = 100;
+const BATCH_WRITE_SIZE: usize = 25;
+const BATCH_CONCURRENCY: usize = 8;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(50);
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+impl DynamoDbClient {
+    pub(super) async fn batch_get_items(&self, keys: Vec<Item>) -> Result<Vec<Item>> {
+        let deadline = Instant::now() + self.batch_deadline;
+        let requests = keys
+            .chunks(BATCH_GET_SIZE)
+            .map(|chunk| self.batch_get_chunk(chunk.to_vec(), deadline))
+            .collect::<Vec<_>>();
+
+        let items: Vec<Vec<Item>> = stream::iter(requests)
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(items.into_iter().flatten().collect())
+    }
+
+    pub(super) async fn batch_put_items(&self, items: Vec<Item>) -> Result<()> {
+        let deadline = Instant::now() + self.batch_deadline;
+        let chunks = items
+            .chunks(BATCH_WRITE_SIZE)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|item| {
+                        let put = PutRequest::builder().set_item(Some(item.clone())).build()?;
+                        Ok(WriteRequest::builder().put_request(put).build())
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let requests = chunks
+            .into_iter()
+            .map(|chunk| self.batch_write_chunk(chunk, deadline))
+            .collect::<Vec<_>>();
+
+        stream::iter(requests)
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .try_collect::<Vec<()>>()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn batch_get_chunk(&self, mut keys: Vec<Item>, deadline: Instant) -> Result<Vec<Item>> {
+        let mut results = Vec::new();
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let request = KeysAndAttributes::builder().set_keys(Some(keys)).build()?;
+            let resp = self
+                .client
+                .batch_get_item()
+                .request_items(&self.table_name, request)
+                .send()
+                .await?;
+
+            if let Some(mut responses) = resp.responses {
+                results.extend(responses.remove(&self.table_name).unwrap_or_default());
+            }
+
+            keys = resp
+                .unprocessed_keys
+                .and_then(|mut unprocessed| unprocessed.remove(&self.table_name))
+                .map(|unprocessed| unprocessed.keys)
+                .unwrap_or_default();
+
+            if keys.is_empty() {
+                return Ok(results);
+            }
+
+            wait_for_retry(&mut backoff, deadline, keys.len()).await?;
+        }
+    }
+
+    async fn batch_write_chunk(
+        &self,
+        mut requests: Vec<WriteRequest>,
+        deadline: Instant,
+    ) -> Result<()> {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let resp = self
+                .client
+                .batch_write_item()
+                .request_items(&self.table_name, requests)
+                .send()
+                .await?;
+
+            requests = resp
+                .unprocessed_items
+                .and_then(|mut unprocessed| unprocessed.remove(&self.table_name))
+                .unwrap_or_default();
+
+            if requests.is_empty() {
+                return Ok(());
+            }
+
+            wait_for_retry(&mut backoff, deadline, requests.len()).await?;
+        }
+    }
+}
+
+async fn wait_for_retry(
+    backoff: &mut Duration,
+    deadline: Instant,
+    unprocessed: usize,
+) -> Result<()> {
+    if Instant::now() + *backoff > deadline {
+        return Err(TableError::Throttled(
+            format!("Batch deadline exceeded with {unprocessed} unprocessed item(s)").into(),
+        ));
+    }
+
+    warn!("Retrying {unprocessed} unprocessed item(s) in {backoff:?}");
+    tokio::time::sleep(*backoff).await;
+    *backoff = (*backoff * 2).min(MAX_BACKOFF);
+    Ok(())
+}
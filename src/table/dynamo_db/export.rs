@@ -0,0 +1,92 @@
+use super::DynamoDbClient;
+use crate::Table;
+use crate::table::Keyed;
+use crate::table::error::{Result, TableError};
+use crate::table::query::Scan;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::io::{BufRead, Write};
+
+const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// One line of an export.
+#[derive(Serialize, Deserialize)]
+struct Record<T> {
+    pk: String,
+    sk: String,
+    item: T,
+}
+
+impl DynamoDbClient {
+    /// Writes every item of entity `T` matched by `scan` as JSON Lines, returning the count.
+    pub async fn export_entries<T, W>(&self, scan: &Scan, mut writer: W) -> Result<usize>
+    where
+        T: Serialize + DeserializeOwned + Keyed + Send + 'static,
+        W: Write,
+    {
+        let mut entries = std::pin::pin!(self.scan_entries::<T>(scan));
+        let mut count = 0;
+
+        while let Some(item) = entries.try_next().await? {
+            let record = Record {
+                pk: item.pk(),
+                sk: item.sk(),
+                item,
+            };
+            serde_json::to_writer(&mut writer, &record)
+                .map_err(|e| TableError::Export(e.into()))?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| TableError::Export(e.into()))?;
+            count += 1;
+        }
+
+        writer.flush().map_err(|e| TableError::Export(e.into()))?;
+        Ok(count)
+    }
+
+    /// Writes the items of a JSON Lines export back with batched puts, returning the count.
+    ///
+    /// Blank lines are skipped. Lines whose key does not match the item's `Keyed` key are
+    /// rejected, as are malformed lines; batches before the failing line have been written.
+    pub async fn import_entries<T, R>(&self, reader: R) -> Result<usize>
+    where
+        T: Serialize + DeserializeOwned + Keyed + Send + Sync + 'static,
+        R: BufRead,
+    {
+        let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+        let mut count = 0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_error = |e: &dyn std::fmt::Display| {
+                TableError::Export(format!("Line {}: {e}", index + 1).into())
+            };
+
+            let line = line.map_err(|e| line_error(&e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: Record<T> = serde_json::from_str(&line).map_err(|e| line_error(&e))?;
+            if record.pk != record.item.pk() || record.sk != record.item.sk() {
+                return Err(line_error(&format!(
+                    "Key {}:{} does not match item key {}:{}",
+                    record.pk,
+                    record.sk,
+                    record.item.pk(),
+                    record.item.sk()
+                )));
+            }
+
+            batch.push(record.item);
+            if batch.len() == IMPORT_BATCH_SIZE {
+                count += batch.len();
+                self.batch_put_entries(std::mem::take(&mut batch)).await?;
+            }
+        }
+
+        count += batch.len();
+        self.batch_put_entries(batch).await?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,780 @@
+mod batch;
+#[cfg(feature = "table-export")]
+mod export;
+mod scan;
+mod tracked;
+mod transaction;
+
+use crate::Table;
+use crate::table::Keyed;
+#[cfg(feature = "table-encryption")]
+use crate::table::encryption::{self, KeyProvider};
+use crate::table::entity::{EntitySet, RawEntity};
+use crate::table::error::{Result, TableError};
+use crate::table::expression::{Condition, Placeholders, Update, UpdateAction};
+#[cfg(feature = "table-overflow")]
+use crate::table::overflow::{self, ObjectStore};
+use crate::table::query::{Cursor, Page, Query};
+use crate::table::schema::{Index, KeyAttribute, KeySchema, TrackingAttributes};
+use anyhow::anyhow;
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::operation::query::builders::QueryFluentBuilder;
+use aws_sdk_dynamodb::operation::update_item::UpdateItemError;
+use aws_sdk_dynamodb::types::{AttributeValue, ReturnValue};
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+#[cfg(any(feature = "table-encryption", feature = "table-overflow"))]
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use transaction::Transaction;
+
+const DEFAULT_BATCH_DEADLINE: Duration = Duration::from_secs(60);
+const DEFAULT_ENTITY_TYPE_ATTRIBUTE: &str = "EntityType";
+// Leaves headroom under DynamoDB's 400 KB item limit for its own size accounting.
+#[cfg(feature = "table-overflow")]
+const DEFAULT_OVERFLOW_THRESHOLD: usize = 350 * 1024;
+
+/// Written alongside items with encrypted fields.
+pub(crate) const ENVELOPE_ATTRIBUTE: &str = "Envelope";
+
+/// Written alongside items with attributes offloaded to the object store.
+pub(crate) const OVERFLOW_ATTRIBUTE: &str = "Overflow";
+
+type Item = HashMap<String, AttributeValue>;
+
+pub struct DynamoDbClient {
+    client: Client,
+    table_name: String,
+    key_schema: KeySchema,
+    indexes: HashMap<String, Index>,
+    entity_type_attribute: String,
+    tracking_attributes: TrackingAttributes,
+    batch_deadline: Duration,
+    #[cfg(feature = "table-encryption")]
+    key_provider: Option<Arc<dyn KeyProvider>>,
+    #[cfg(feature = "table-overflow")]
+    object_store: Option<Arc<dyn ObjectStore>>,
+    #[cfg(feature = "table-overflow")]
+    overflow_threshold: usize,
+}
+
+impl DynamoDbClient {
+    pub fn builder() -> DynamoDbClientBuilder {
+        DynamoDbClientBuilder::default()
+    }
+
+    /// Reads every item in the partition, decoding each into the matching variant of `E`.
+    pub async fn query_entities<E: EntitySet>(&self, query: &Query) -> Result<Vec<E>> {
+        let mut query = query.clone();
+        let mut results = Vec::new();
+
+        loop {
+            let resp = self.query_request(&query, None)?.send().await?;
+
+            for item in resp.items.unwrap_or_default() {
+                let item = self.restore_item(item, None).await?;
+                let raw = RawEntity {
+                    entity_type: self.entity_type_of(&item).map(str::to_owned),
+                    item,
+                };
+                results.extend(E::from_raw(raw)?);
+            }
+
+            let limit_reached = query
+                .limit
+                .is_some_and(|limit| results.len() as i32 >= limit);
+            match resp.last_evaluated_key {
+                Some(key) if !limit_reached => query.cursor = Some(Cursor(key)),
+                _ => break,
+            }
+        }
+
+        if let Some(limit) = query.limit {
+            results.truncate(limit as usize);
+        }
+
+        Ok(results)
+    }
+
+    fn placeholders(&self) -> Placeholders {
+        Placeholders::new(&self.key_schema)
+    }
+
+    /// Builds the primary key; `sk` is ignored for tables without a sort key.
+    fn key(&self, pk: &str, sk: &str) -> Item {
+        let partition_key = &self.key_schema.partition_key;
+        let mut key = HashMap::from([(partition_key.name.clone(), partition_key.attribute(pk))]);
+
+        if let Some(sort_key) = &self.key_schema.sort_key {
+            key.insert(sort_key.name.clone(), sort_key.attribute(sk));
+        }
+
+        key
+    }
+
+    fn item_map<T>(&self, item: &T) -> Result<Item>
+    where
+        T: Serialize + Keyed,
+    {
+        let mut item_map: Item = serde_dynamo::to_item(item)?;
+
+        for (attribute, value) in item.index_keys() {
+            let value = self.index_key_attribute(&attribute).attribute(&value);
+            item_map.insert(attribute, value);
+        }
+
+        if let Some(entity_type) = T::entity_type() {
+            item_map.insert(
+                self.entity_type_attribute.clone(),
+                AttributeValue::S(entity_type.to_owned()),
+            );
+        }
+
+        item_map.extend(self.key(&item.pk(), &item.sk()));
+        Ok(item_map)
+    }
+
+    /// Encrypts `encrypted_fields` and offloads oversized attributes before `item` is written.
+    async fn prepare_item(
+        &self,
+        item: &mut Item,
+        pk: &str,
+        sk: &str,
+        encrypted_fields: &[&str],
+    ) -> Result<()> {
+        self.encrypt_fields(item, encrypted_fields).await?;
+        self.offload_attributes(item, pk, sk).await
+    }
+
+    /// Reverses `prepare_item`, restoring only `attributes` if given.
+    async fn restore_item(&self, item: Item, attributes: Option<&[&str]>) -> Result<Item> {
+        let item = self.rehydrate_attributes(item, attributes).await?;
+        self.decrypt_fields(item).await
+    }
+
+    #[cfg(feature = "table-overflow")]
+    async fn offload_attributes(&self, item: &mut Item, pk: &str, sk: &str) -> Result<()> {
+        let Some(object_store) = &self.object_store else {
+            return Ok(());
+        };
+
+        let attributes = &self.tracking_attributes;
+        let pinned = std::iter::once(&self.key_schema)
+            .chain(self.indexes.values().map(|index| &index.key_schema))
+            .flat_map(|key_schema| {
+                std::iter::once(&key_schema.partition_key).chain(key_schema.sort_key.as_ref())
+            })
+            .map(|key| key.name.as_str())
+            .chain([
+                self.entity_type_attribute.as_str(),
+                ENVELOPE_ATTRIBUTE,
+                attributes.created_at.as_str(),
+                attributes.updated_at.as_str(),
+                attributes.expires_at.as_str(),
+                attributes.version.as_str(),
+            ])
+            .collect::<Vec<_>>();
+
+        overflow::offload_attributes(
+            object_store.as_ref(),
+            item,
+            pk,
+            sk,
+            self.overflow_threshold,
+            &pinned,
+        )
+        .await
+    }
+
+    #[cfg(not(feature = "table-overflow"))]
+    async fn offload_attributes(&self, _item: &mut Item, _pk: &str, _sk: &str) -> Result<()> {
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "table-overflow"), allow(unused_variables))]
+    async fn rehydrate_attributes(&self, item: Item, attributes: Option<&[&str]>) -> Result<Item> {
+        if !item.contains_key(OVERFLOW_ATTRIBUTE) {
+            return Ok(item);
+        }
+
+        #[cfg(feature = "table-overflow")]
+        if let Some(object_store) = &self.object_store {
+            let mut item = item;
+            overflow::rehydrate_attributes(object_store.as_ref(), &mut item, attributes).await?;
+            return Ok(item);
+        }
+
+        Err(TableError::InvalidRequest(
+            "Item has offloaded attributes but no object store is configured".into(),
+        ))
+    }
+
+    /// Deletes objects the previous version of an item pointed to once it is replaced or deleted.
+    #[cfg(feature = "table-overflow")]
+    async fn delete_unreferenced(&self, old: Option<Item>, new: Option<&Item>) -> Result<()> {
+        match (&self.object_store, old) {
+            (Some(object_store), Some(old)) => {
+                overflow::delete_unreferenced(object_store.as_ref(), &old, new).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(not(feature = "table-overflow"))]
+    async fn delete_unreferenced(&self, _old: Option<Item>, _new: Option<&Item>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Encrypts `fields` in place, failing rather than writing them in plaintext.
+    async fn encrypt_fields(&self, item: &mut Item, fields: &[&str]) -> Result<()> {
+        if !fields.iter().any(|field| item.contains_key(*field)) {
+            return Ok(());
+        }
+
+        #[cfg(feature = "table-encryption")]
+        if let Some(key_provider) = &self.key_provider {
+            return encryption::encrypt_fields(key_provider.as_ref(), item, fields).await;
+        }
+
+        Err(TableError::InvalidRequest(
+            "Item has encrypted fields but no key provider is configured".into(),
+        ))
+    }
+
+    async fn decrypt_fields(&self, item: Item) -> Result<Item> {
+        if !item.contains_key(ENVELOPE_ATTRIBUTE) {
+            return Ok(item);
+        }
+
+        #[cfg(feature = "table-encryption")]
+        if let Some(key_provider) = &self.key_provider {
+            let mut item = item;
+            encryption::decrypt_fields(key_provider.as_ref(), &mut item).await?;
+            return Ok(item);
+        }
+
+        Err(TableError::InvalidRequest(
+            "Item is encrypted but no key provider is configured".into(),
+        ))
+    }
+
+    async fn decode<T: DeserializeOwned>(&self, item: Item) -> Result<T> {
+        let item = self.restore_item(item, None).await?;
+        Ok(serde_dynamo::from_item(item)?)
+    }
+
+    fn entity_type_of<'a>(&self, item: &'a Item) -> Option<&'a str> {
+        match item.get(&self.entity_type_attribute) {
+            Some(AttributeValue::S(entity_type)) => Some(entity_type),
+            _ => None,
+        }
+    }
+
+    fn is_entity<T: Keyed>(&self, item: &Item) -> bool {
+        T::entity_type().is_none_or(|entity_type| self.entity_type_of(item) == Some(entity_type))
+    }
+
+    fn index_key_attribute(&self, attribute: &str) -> KeyAttribute {
+        self.indexes
+            .values()
+            .flat_map(|index| {
+                std::iter::once(&index.key_schema.partition_key)
+                    .chain(index.key_schema.sort_key.as_ref())
+            })
+            .find(|key| key.name == attribute)
+            .cloned()
+            .unwrap_or_else(|| KeyAttribute::string(attribute))
+    }
+
+    /// Reads an item of entity `T`; the entity type, envelope and overflow attributes are always
+    /// projected so the item can be checked and restored.
+    async fn get_item<T: Keyed>(
+        &self,
+        pk: &str,
+        sk: &str,
+        attributes: Option<&[&str]>,
+    ) -> Result<Option<Item>> {
+        let mut placeholders = self.placeholders();
+        let projection_expression = attributes.map(|attributes| {
+            T::entity_type()
+                .map(|_| self.entity_type_attribute.as_str())
+                .into_iter()
+                .chain((!T::encrypted_fields().is_empty()).then_some(ENVELOPE_ATTRIBUTE))
+                .chain(self.offloads().then_some(OVERFLOW_ATTRIBUTE))
+                .chain(attributes.iter().copied())
+                .map(|attribute| placeholders.name(attribute))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+
+        let resp = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(self.key(pk, sk)))
+            .set_projection_expression(projection_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .send()
+            .await?;
+
+        Ok(resp.item.filter(|item| self.is_entity::<T>(item)))
+    }
+
+    async fn put_item<T>(&self, item: &T, condition: Option<&Condition>) -> Result<()>
+    where
+        T: Serialize + Keyed,
+    {
+        let (pk, sk) = (item.pk(), item.sk());
+        let mut item_map = self.item_map(item)?;
+        self.prepare_item(&mut item_map, &pk, &sk, T::encrypted_fields())
+            .await?;
+        self.put_item_map(item_map, &pk, &sk, condition).await
+    }
+
+    fn offloads(&self) -> bool {
+        #[cfg(feature = "table-overflow")]
+        return self.object_store.is_some();
+        #[cfg(not(feature = "table-overflow"))]
+        false
+    }
+
+    fn old_values(&self) -> Option<ReturnValue> {
+        self.offloads().then_some(ReturnValue::AllOld)
+    }
+
+    async fn put_item_map(
+        &self,
+        item_map: Item,
+        pk: &str,
+        sk: &str,
+        condition: Option<&Condition>,
+    ) -> Result<()> {
+        let mut placeholders = self.placeholders();
+        let condition_expression = condition.map(|condition| condition.render(&mut placeholders));
+        let new = self.offloads().then(|| item_map.clone());
+
+        let resp = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(item_map))
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .set_return_values(self.old_values())
+            .send()
+            .await
+            .map_err(|e| {
+                condition_error(
+                    e,
+                    pk,
+                    sk,
+                    PutItemError::is_conditional_check_failed_exception,
+                )
+            })?;
+
+        self.delete_unreferenced(resp.attributes, new.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Applies `update` and returns the attributes selected by `return_values`.
+    async fn update_item<T: Keyed>(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        return_values: ReturnValue,
+    ) -> Result<Item> {
+        let updates_encrypted_field = update.actions.iter().any(|action| match action {
+            UpdateAction::Set(name, _)
+            | UpdateAction::Add(name, _)
+            | UpdateAction::Delete(name, _) => T::encrypted_fields().contains(&name.as_str()),
+            UpdateAction::Remove(_) => false,
+        });
+        if updates_encrypted_field {
+            return Err(TableError::InvalidRequest(
+                "Encrypted fields can only be written with put_entry".into(),
+            ));
+        }
+
+        let mut placeholders = self.placeholders();
+        let update_expression = update.render(&mut placeholders);
+        let condition_expression = update
+            .condition
+            .as_ref()
+            .map(|condition| condition.render(&mut placeholders));
+
+        let resp = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(self.key(pk, sk)))
+            .update_expression(update_expression)
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .return_values(return_values.clone())
+            .send()
+            .await
+            .map_err(|e| {
+                condition_error(
+                    e,
+                    pk,
+                    sk,
+                    UpdateItemError::is_conditional_check_failed_exception,
+                )
+            })?;
+
+        // Updated attributes that end up unset are not returned at all.
+        match (resp.attributes, return_values) {
+            (Some(item), _) => Ok(item),
+            (None, ReturnValue::UpdatedNew) => Ok(Item::new()),
+            (None, _) => Err(TableError::Service(
+                format!("No attributes returned for {pk}:{sk}").into(),
+            )),
+        }
+    }
+
+    async fn delete_item(&self, pk: &str, sk: &str, condition: Option<&Condition>) -> Result<()> {
+        let mut placeholders = self.placeholders();
+        let condition_expression = condition.map(|condition| condition.render(&mut placeholders));
+
+        let resp = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .set_key(Some(self.key(pk, sk)))
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .set_return_values(self.old_values())
+            .send()
+            .await
+            .map_err(|e| {
+                condition_error(
+                    e,
+                    pk,
+                    sk,
+                    DeleteItemError::is_conditional_check_failed_exception,
+                )
+            })?;
+
+        self.delete_unreferenced(resp.attributes, None).await?;
+
+        Ok(())
+    }
+
+    fn key_schema_of(&self, index_name: Option<&str>) -> Result<&KeySchema> {
+        match index_name {
+            Some(index_name) => self
+                .indexes
+                .get(index_name)
+                .map(|index| &index.key_schema)
+                .ok_or_else(|| {
+                    TableError::InvalidRequest(format!("Unknown index {index_name}").into())
+                }),
+            None => Ok(&self.key_schema),
+        }
+    }
+
+    fn entity_type_filter(
+        &self,
+        entity_type: Option<&str>,
+        placeholders: &mut Placeholders,
+    ) -> Option<String> {
+        entity_type.map(|entity_type| {
+            format!(
+                "{} = {}",
+                placeholders.name(&self.entity_type_attribute),
+                placeholders.value(&AttributeValue::S(entity_type.to_owned()))
+            )
+        })
+    }
+
+    fn query_request(
+        &self,
+        query: &Query,
+        entity_type: Option<&str>,
+    ) -> Result<QueryFluentBuilder> {
+        let key_schema = self.key_schema_of(query.index.as_deref())?;
+
+        let mut placeholders = self.placeholders();
+        let mut key_condition = format!(
+            "{} = {}",
+            placeholders.name(&key_schema.partition_key.name),
+            placeholders.value(&key_schema.partition_key.attribute(&query.pk))
+        );
+
+        if let Some(condition) = &query.sort_key {
+            let sort_key = key_schema.sort_key.as_ref().ok_or_else(|| {
+                TableError::InvalidRequest("Sort key condition on a key without a sort key".into())
+            })?;
+            key_condition = format!(
+                "{key_condition} AND {}",
+                condition.render(sort_key, &mut placeholders)
+            );
+        }
+
+        let filter_expression = self.entity_type_filter(entity_type, &mut placeholders);
+
+        Ok(self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .set_index_name(query.index.clone())
+            .key_condition_expression(key_condition)
+            .set_filter_expression(filter_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .scan_index_forward(query.scan_forward)
+            .set_limit(query.limit)
+            .set_exclusive_start_key(query.cursor.clone().map(|cursor| cursor.0)))
+    }
+}
+
+fn condition_error<E, R>(
+    error: SdkError<E, R>,
+    pk: &str,
+    sk: &str,
+    is_condition_failed: impl Fn(&E) -> bool,
+) -> TableError
+where
+    SdkError<E, R>: Into<TableError> + std::error::Error + Send + Sync + 'static,
+{
+    match &error {
+        SdkError::ServiceError(service_error) if is_condition_failed(service_error.err()) => {
+            TableError::ConditionFailed {
+                pk: pk.to_owned(),
+                sk: sk.to_owned(),
+                source: Some(Box::new(error)),
+            }
+        }
+        _ => error.into(),
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> Table<T> for DynamoDbClient
+where
+    T: Serialize + DeserializeOwned + Keyed + Send + Sync + 'static,
+{
+    async fn get_entry(&self, pk: &str, sk: &str) -> Result<T> {
+        let item = self
+            .get_item::<T>(pk, sk, None)
+            .await?
+            .ok_or_else(|| TableError::NotFound {
+                pk: pk.to_owned(),
+                sk: sk.to_owned(),
+            })?;
+        self.decode(item).await
+    }
+
+    async fn find_projection<P>(&self, pk: &str, sk: &str, attributes: &[&str]) -> Result<Option<P>>
+    where
+        P: DeserializeOwned + Send,
+    {
+        match self.get_item::<T>(pk, sk, Some(attributes)).await? {
+            Some(item) => {
+                let item = self.restore_item(item, Some(attributes)).await?;
+                Ok(Some(serde_dynamo::from_item(item)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put_entry(&self, item: T) -> Result<()> {
+        self.put_item(&item, None).await
+    }
+
+    async fn put_entry_if(&self, item: T, condition: &Condition) -> Result<()> {
+        self.put_item(&item, Some(condition)).await
+    }
+
+    async fn delete_entry(&self, pk: &str, sk: &str) -> Result<()> {
+        self.delete_item(pk, sk, None).await
+    }
+
+    async fn delete_entry_if(&self, pk: &str, sk: &str, condition: &Condition) -> Result<()> {
+        self.delete_item(pk, sk, Some(condition)).await
+    }
+
+    async fn update_entry(&self, pk: &str, sk: &str, update: &Update) -> Result<T> {
+        let item = self
+            .update_item::<T>(pk, sk, update, ReturnValue::AllNew)
+            .await?;
+        self.decode(item).await
+    }
+
+    async fn update_attribute(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        attribute: &str,
+    ) -> Result<Option<AttributeValue>> {
+        let mut item = self
+            .update_item::<T>(pk, sk, update, ReturnValue::UpdatedNew)
+            .await?;
+        Ok(item.remove(attribute))
+    }
+
+    async fn batch_get_entries(&self, keys: &[(&str, &str)]) -> Result<Vec<T>> {
+        let keys = keys.iter().map(|(pk, sk)| self.key(pk, sk)).collect();
+
+        let mut results = Vec::new();
+        for item in self.batch_get_items(keys).await? {
+            if self.is_entity::<T>(&item) {
+                results.push(self.decode(item).await?);
+            }
+        }
+        Ok(results)
+    }
+
+    async fn batch_put_entries(&self, items: Vec<T>) -> Result<()> {
+        let mut item_maps = Vec::with_capacity(items.len());
+        for item in &items {
+            let mut item_map = self.item_map(item)?;
+            self.prepare_item(&mut item_map, &item.pk(), &item.sk(), T::encrypted_fields())
+                .await?;
+            item_maps.push(item_map);
+        }
+
+        self.batch_put_items(item_maps).await
+    }
+
+    async fn query(&self, query: &Query) -> Result<Page<T>> {
+        let resp = self.query_request(query, T::entity_type())?.send().await?;
+
+        let mut items = Vec::new();
+        for item in resp.items.unwrap_or_default() {
+            items.push(self.decode(item).await?);
+        }
+
+        Ok(Page {
+            items,
+            cursor: resp.last_evaluated_key.map(Cursor),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct DynamoDbClientBuilder {
+    client: Option<Client>,
+    table_name: Option<String>,
+    key_schema: Option<KeySchema>,
+    indexes: HashMap<String, Index>,
+    entity_type_attribute: Option<String>,
+    tracking_attributes: Option<TrackingAttributes>,
+    batch_deadline: Option<Duration>,
+    #[cfg(feature = "table-encryption")]
+    key_provider: Option<Arc<dyn KeyProvider>>,
+    #[cfg(feature = "table-overflow")]
+    object_store: Option<Arc<dyn ObjectStore>>,
+    #[cfg(feature = "table-overflow")]
+    overflow_threshold: Option<usize>,
+}
+
+impl DynamoDbClientBuilder {
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn table_name(mut self, table_name: &str) -> Self {
+        self.table_name = Some(table_name.to_owned());
+        self
+    }
+
+    /// Defaults to string `PK` and `SK` attributes.
+    pub fn key_schema(mut self, key_schema: KeySchema) -> Self {
+        self.key_schema = Some(key_schema);
+        self
+    }
+
+    pub fn index(mut self, index: Index) -> Self {
+        self.indexes.insert(index.name.clone(), index);
+        self
+    }
+
+    /// Attribute holding `Keyed::entity_type`, `EntityType` by default.
+    pub fn entity_type_attribute(mut self, attribute: &str) -> Self {
+        self.entity_type_attribute = Some(attribute.to_owned());
+        self
+    }
+
+    /// Attributes stamped by `DynamoDbClient::put_tracked`.
+    pub fn tracking_attributes(mut self, tracking_attributes: TrackingAttributes) -> Self {
+        self.tracking_attributes = Some(tracking_attributes);
+        self
+    }
+
+    /// How long batch operations keep retrying unprocessed items before giving up.
+    pub fn batch_deadline(mut self, batch_deadline: Duration) -> Self {
+        self.batch_deadline = Some(batch_deadline);
+        self
+    }
+
+    /// Required to write or read items of types with `Keyed::encrypted_fields`.
+    #[cfg(feature = "table-encryption")]
+    pub fn key_provider(mut self, key_provider: Arc<dyn KeyProvider>) -> Self {
+        self.key_provider = Some(key_provider);
+        self
+    }
+
+    /// Offloads the largest attributes of items over the overflow threshold to `object_store`,
+    /// and is required to read items written that way.
+    ///
+    /// Objects are cleaned up when single items are replaced or deleted, but not by batch or
+    /// transactional writes. `update_entry` does not touch offloaded attributes; an attribute it
+    /// sets takes precedence over the offloaded value.
+    #[cfg(feature = "table-overflow")]
+    pub fn object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = Some(object_store);
+        self
+    }
+
+    /// Serialised item size in bytes above which attributes are offloaded, 350 KiB by default.
+    #[cfg(feature = "table-overflow")]
+    pub fn overflow_threshold(mut self, overflow_threshold: usize) -> Self {
+        self.overflow_threshold = Some(overflow_threshold);
+        self
+    }
+
+    pub async fn build(self) -> anyhow::Result<DynamoDbClient> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let config = aws_config::load_from_env().await;
+                Client::new(&config)
+            }
+        };
+
+        let table_name = self.table_name.ok_or(anyhow!("Missing table name"))?;
+
+        Ok(DynamoDbClient {
+            client,
+            table_name,
+            key_schema: self.key_schema.unwrap_or_default(),
+            indexes: self.indexes,
+            entity_type_attribute: self
+                .entity_type_attribute
+                .unwrap_or_else(|| DEFAULT_ENTITY_TYPE_ATTRIBUTE.to_owned()),
+            tracking_attributes: self.tracking_attributes.unwrap_or_default(),
+            batch_deadline: self.batch_deadline.unwrap_or(DEFAULT_BATCH_DEADLINE),
+            #[cfg(feature = "table-encryption")]
+            key_provider: self.key_provider,
+            #[cfg(feature = "table-overflow")]
+            object_store: self.object_store,
+            #[cfg(feature = "table-overflow")]
+            overflow_threshold: self
+                .overflow_threshold
+                .unwrap_or(DEFAULT_OVERFLOW_THRESHOLD),
+        })
+    }
+}
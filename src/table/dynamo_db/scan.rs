@@ -0,0 +1,85 @@
+use super::{DynamoDbClient, Item};
+use crate::table::Keyed;
+use crate::table::error::{Result, TableError};
+use crate::table::query::Scan;
+use aws_sdk_dynamodb::operation::scan::builders::ScanFluentBuilder;
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+impl DynamoDbClient {
+    /// Streams every item of entity `T`, reading the scan's segments in parallel.
+    ///
+    /// Items from different segments interleave, so no ordering is guaranteed. Only one page
+    /// per segment is held in memory at a time.
+    pub fn scan_entries<T>(&self, scan: &Scan) -> impl Stream<Item = Result<T>> + Send + '_
+    where
+        T: DeserializeOwned + Keyed + Send + 'static,
+    {
+        let segments = (0..scan.segments)
+            .map(|segment| self.scan_segment::<T>(scan, segment))
+            .collect::<Vec<_>>();
+
+        stream::select_all(segments)
+    }
+
+    fn scan_segment<T>(&self, scan: &Scan, segment: i32) -> BoxStream<'_, Result<T>>
+    where
+        T: DeserializeOwned + Keyed + Send + 'static,
+    {
+        let request = match self.scan_request::<T>(scan, segment) {
+            Ok(request) => request,
+            Err(e) => return stream::once(async { Err(e) }).boxed(),
+        };
+
+        // `None` once the segment is exhausted, otherwise the key to continue from.
+        stream::try_unfold(Some(None::<Item>), move |start_key| {
+            let request = request.clone();
+            async move {
+                let Some(start_key) = start_key else {
+                    return Ok(None);
+                };
+
+                let resp = request.set_exclusive_start_key(start_key).send().await?;
+                let mut items = Vec::new();
+                for item in resp.items.unwrap_or_default() {
+                    items.push(self.decode::<T>(item).await?);
+                }
+
+                Ok::<_, TableError>(Some((items, resp.last_evaluated_key.map(Some))))
+            }
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    fn scan_request<T: Keyed>(&self, scan: &Scan, segment: i32) -> Result<ScanFluentBuilder> {
+        self.key_schema_of(scan.index.as_deref())?;
+
+        let mut placeholders = self.placeholders();
+        let mut filters = scan
+            .filter
+            .iter()
+            .map(|condition| condition.render(&mut placeholders))
+            .collect::<Vec<_>>();
+        filters.extend(self.entity_type_filter(T::entity_type(), &mut placeholders));
+        let filter_expression = match filters.len() {
+            0 => None,
+            1 => filters.into_iter().next(),
+            _ => Some(format!("({})", filters.join(") AND ("))),
+        };
+
+        let parallel = scan.segments > 1;
+        Ok(self
+            .client
+            .scan()
+            .table_name(&self.table_name)
+            .set_index_name(scan.index.clone())
+            .set_filter_expression(filter_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .set_limit(scan.page_size)
+            .set_segment(parallel.then_some(segment))
+            .set_total_segments(parallel.then_some(scan.segments)))
+    }
+}
@@ -0,0 +1,55 @@
+use super::DynamoDbClient;
+use crate::model::Tracked;
+use crate::table::error::{Result, TableError};
+use crate::table::expression::{Condition, IntoAttribute};
+use serde::{Serialize, de::DeserializeOwned};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl DynamoDbClient {
+    /// Writes `item` stamped with timestamps, expiry and the next version number.
+    ///
+    /// Fails with `TableError::ConditionFailed` if another writer has bumped the version since `item` was
+    /// read. `created_at` is kept from `item` when present, so read it back along with the rest.
+    pub async fn put_tracked<T>(&self, item: T) -> Result<T>
+    where
+        T: Tracked + Serialize + DeserializeOwned,
+    {
+        let attributes = &self.tracking_attributes;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| TableError::Service(e.into()))?;
+        let version = item.version();
+
+        let mut item_map = self.item_map(&item)?;
+        item_map.insert(attributes.version.clone(), (version + 1).into_attribute());
+        item_map.insert(
+            attributes.updated_at.clone(),
+            now.as_secs().into_attribute(),
+        );
+        item_map
+            .entry(attributes.created_at.clone())
+            .or_insert_with(|| now.as_secs().into_attribute());
+
+        if let Some(time_to_live) = T::time_to_live() {
+            let expires_at = now + time_to_live;
+            item_map.insert(
+                attributes.expires_at.clone(),
+                expires_at.as_secs().into_attribute(),
+            );
+        }
+
+        let condition = match version {
+            0 => Condition::ItemNotExists,
+            version => Condition::version_equals(&attributes.version, version),
+        };
+
+        let (pk, sk) = (item.pk(), item.sk());
+        let mut stored = item_map.clone();
+        self.prepare_item(&mut stored, &pk, &sk, T::encrypted_fields())
+            .await?;
+        self.put_item_map(stored, &pk, &sk, Some(&condition))
+            .await?;
+
+        Ok(serde_dynamo::from_item(item_map)?)
+    }
+}
@@ -0,0 +1,214 @@
+use super::DynamoDbClient;
+use crate::table::Keyed;
+use crate::table::error::{Result, TableError, TransactionFailure};
+use crate::table::expression::{Condition, Update};
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::types::{self, TransactWriteItem};
+use serde::Serialize;
+
+const MAX_TRANSACTION_ITEMS: usize = 100;
+
+/// Collects writes across any `Keyed` types and applies them atomically on `commit`.
+pub struct Transaction<'a> {
+    client: &'a DynamoDbClient,
+    items: Vec<TransactWriteItem>,
+    keys: Vec<(String, String)>,
+    // Puts to prepare on commit, by position in `items`, with their encrypted fields.
+    puts: Vec<(usize, &'static [&'static str])>,
+}
+
+impl DynamoDbClient {
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction {
+            client: self,
+            items: Vec::new(),
+            keys: Vec::new(),
+            puts: Vec::new(),
+        }
+    }
+}
+
+impl Transaction<'_> {
+    pub fn put<T>(self, item: &T) -> Result<Self>
+    where
+        T: Serialize + Keyed,
+    {
+        self.put_item(item, None)
+    }
+
+    pub fn put_if<T>(self, item: &T, condition: &Condition) -> Result<Self>
+    where
+        T: Serialize + Keyed,
+    {
+        self.put_item(item, Some(condition))
+    }
+
+    pub fn update(mut self, pk: &str, sk: &str, update: &Update) -> Result<Self> {
+        let mut placeholders = self.client.placeholders();
+        let update_expression = update.render(&mut placeholders);
+        let condition_expression = update
+            .condition
+            .as_ref()
+            .map(|condition| condition.render(&mut placeholders));
+
+        let operation = types::Update::builder()
+            .table_name(&self.client.table_name)
+            .set_key(Some(self.client.key(pk, sk)))
+            .update_expression(update_expression)
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .build()?;
+
+        self.items
+            .push(TransactWriteItem::builder().update(operation).build());
+        self.keys.push((pk.to_owned(), sk.to_owned()));
+        Ok(self)
+    }
+
+    pub fn delete(self, pk: &str, sk: &str) -> Result<Self> {
+        self.delete_item(pk, sk, None)
+    }
+
+    pub fn delete_if(self, pk: &str, sk: &str, condition: &Condition) -> Result<Self> {
+        self.delete_item(pk, sk, Some(condition))
+    }
+
+    /// Fails the whole transaction unless `condition` holds for the given item.
+    pub fn condition_check(mut self, pk: &str, sk: &str, condition: &Condition) -> Result<Self> {
+        let mut placeholders = self.client.placeholders();
+        let condition_expression = condition.render(&mut placeholders);
+
+        let operation = types::ConditionCheck::builder()
+            .table_name(&self.client.table_name)
+            .set_key(Some(self.client.key(pk, sk)))
+            .condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .build()?;
+
+        self.items.push(
+            TransactWriteItem::builder()
+                .condition_check(operation)
+                .build(),
+        );
+        self.keys.push((pk.to_owned(), sk.to_owned()));
+        Ok(self)
+    }
+
+    pub async fn commit(mut self) -> Result<()> {
+        if self.items.is_empty() {
+            return Ok(());
+        }
+
+        if self.items.len() > MAX_TRANSACTION_ITEMS {
+            return Err(TableError::InvalidRequest(
+                format!(
+                    "Transaction has {} items, the limit is {}",
+                    self.items.len(),
+                    MAX_TRANSACTION_ITEMS
+                )
+                .into(),
+            ));
+        }
+
+        for (index, encrypted_fields) in &self.puts {
+            let (pk, sk) = &self.keys[*index];
+            if let Some(put) = self.items[*index].put.as_mut() {
+                self.client
+                    .prepare_item(&mut put.item, pk, sk, encrypted_fields)
+                    .await?;
+            }
+        }
+
+        self.client
+            .client
+            .transact_write_items()
+            .set_transact_items(Some(self.items))
+            .send()
+            .await
+            .map_err(|e| cancellation_error(e, &self.keys))?;
+
+        Ok(())
+    }
+
+    fn put_item<T>(mut self, item: &T, condition: Option<&Condition>) -> Result<Self>
+    where
+        T: Serialize + Keyed,
+    {
+        let mut placeholders = self.client.placeholders();
+        let condition_expression = condition.map(|condition| condition.render(&mut placeholders));
+
+        let operation = types::Put::builder()
+            .table_name(&self.client.table_name)
+            .set_item(Some(self.client.item_map(item)?))
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .build()?;
+
+        self.puts.push((self.items.len(), T::encrypted_fields()));
+        self.items
+            .push(TransactWriteItem::builder().put(operation).build());
+        self.keys.push((item.pk(), item.sk()));
+        Ok(self)
+    }
+
+    fn delete_item(mut self, pk: &str, sk: &str, condition: Option<&Condition>) -> Result<Self> {
+        let mut placeholders = self.client.placeholders();
+        let condition_expression = condition.map(|condition| condition.render(&mut placeholders));
+
+        let operation = types::Delete::builder()
+            .table_name(&self.client.table_name)
+            .set_key(Some(self.client.key(pk, sk)))
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(placeholders.names())
+            .set_expression_attribute_values(placeholders.values())
+            .build()?;
+
+        self.items
+            .push(TransactWriteItem::builder().delete(operation).build());
+        self.keys.push((pk.to_owned(), sk.to_owned()));
+        Ok(self)
+    }
+}
+
+fn cancellation_error<R>(
+    error: SdkError<TransactWriteItemsError, R>,
+    keys: &[(String, String)],
+) -> TableError
+where
+    SdkError<TransactWriteItemsError, R>:
+        Into<TableError> + std::error::Error + Send + Sync + 'static,
+{
+    let SdkError::ServiceError(service_error) = &error else {
+        return error.into();
+    };
+    let TransactWriteItemsError::TransactionCanceledException(canceled) = service_error.err()
+    else {
+        return error.into();
+    };
+
+    let failures = canceled
+        .cancellation_reasons()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, reason)| {
+            let code = reason.code().filter(|code| *code != "None")?;
+            let (pk, sk) = keys.get(index)?.clone();
+            Some(TransactionFailure {
+                index,
+                pk,
+                sk,
+                code: code.to_owned(),
+                message: reason.message().map(str::to_owned),
+            })
+        })
+        .collect();
+
+    TableError::TransactionCanceled {
+        failures,
+        source: Box::new(error),
+    }
+}
@@ -0,0 +1,177 @@
+use crate::table::codec;
+use crate::table::dynamo_db::ENVELOPE_ATTRIBUTE;
+use crate::table::error::{Result, TableError};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+
+const DATA_KEY: &str = "DataKey";
+const FIELDS: &str = "Fields";
+const NONCE_LEN: usize = 12;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// A 256-bit data key, in plaintext for encrypting one item and wrapped for storing with it.
+pub struct DataKey {
+    pub plaintext: Vec<u8>,
+    pub wrapped: Vec<u8>,
+}
+
+/// Issues and unwraps data keys, e.g. through KMS `GenerateDataKey` and `Decrypt`.
+#[async_trait::async_trait]
+pub trait KeyProvider: Send + Sync {
+    async fn generate_data_key(&self) -> anyhow::Result<DataKey>;
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Wraps data keys with a master key held in memory, for tests and local runs.
+pub struct LocalKeyProvider {
+    cipher: Aes256Gcm,
+}
+
+impl LocalKeyProvider {
+    pub fn new(master_key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(master_key.into()),
+        }
+    }
+
+    /// Uses a random master key; data written with it is unreadable once the provider is dropped.
+    pub fn generate() -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&Aes256Gcm::generate_key(&mut OsRng)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for LocalKeyProvider {
+    async fn generate_data_key(&self) -> anyhow::Result<DataKey> {
+        let plaintext = Aes256Gcm::generate_key(&mut OsRng).to_vec();
+        let wrapped = seal(&self.cipher, &plaintext, &[])?;
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(open(&self.cipher, wrapped, &[])?)
+    }
+}
+
+/// Replaces each present field with its ciphertext under a fresh data key.
+pub(crate) async fn encrypt_fields(
+    key_provider: &dyn KeyProvider,
+    item: &mut Item,
+    fields: &[&str],
+) -> Result<()> {
+    let data_key = key_provider
+        .generate_data_key()
+        .await
+        .map_err(|e| TableError::Encryption(e.into()))?;
+    let cipher = data_key_cipher(&data_key.plaintext)?;
+
+    let mut encrypted = Vec::new();
+    for field in fields {
+        let Some(value) = item.get(*field) else {
+            continue;
+        };
+
+        let mut plaintext = Vec::new();
+        codec::encode(value, &mut plaintext).map_err(|e| TableError::Encryption(e.into()))?;
+        let ciphertext = seal(&cipher, &plaintext, field.as_bytes())
+            .map_err(|e| TableError::Encryption(e.into()))?;
+
+        item.insert(
+            (*field).to_owned(),
+            AttributeValue::B(Blob::new(ciphertext)),
+        );
+        encrypted.push((*field).to_owned());
+    }
+
+    // String sets cannot be empty, so items without any of the fields get no envelope.
+    if !encrypted.is_empty() {
+        let envelope = HashMap::from([
+            (
+                DATA_KEY.to_owned(),
+                AttributeValue::B(Blob::new(data_key.wrapped)),
+            ),
+            (FIELDS.to_owned(), AttributeValue::Ss(encrypted)),
+        ]);
+        item.insert(ENVELOPE_ATTRIBUTE.to_owned(), AttributeValue::M(envelope));
+    }
+
+    Ok(())
+}
+
+/// Restores the fields listed in the item's envelope; fields missing from a projection are skipped.
+pub(crate) async fn decrypt_fields(key_provider: &dyn KeyProvider, item: &mut Item) -> Result<()> {
+    let Some(envelope) = item.remove(ENVELOPE_ATTRIBUTE) else {
+        return Ok(());
+    };
+
+    let AttributeValue::M(envelope) = envelope else {
+        return Err(malformed("envelope"));
+    };
+    let (Some(AttributeValue::B(wrapped)), Some(AttributeValue::Ss(fields))) =
+        (envelope.get(DATA_KEY), envelope.get(FIELDS))
+    else {
+        return Err(malformed("envelope"));
+    };
+
+    let data_key = key_provider
+        .unwrap_data_key(wrapped.as_ref())
+        .await
+        .map_err(|e| TableError::Encryption(e.into()))?;
+    let cipher = data_key_cipher(&data_key)?;
+
+    for field in fields {
+        let ciphertext = match item.get(field) {
+            Some(AttributeValue::B(ciphertext)) => ciphertext,
+            Some(_) => return Err(malformed(field)),
+            None => continue,
+        };
+
+        let plaintext = open(&cipher, ciphertext.as_ref(), field.as_bytes())
+            .map_err(|e| TableError::Encryption(e.into()))?;
+        let value = codec::decode(&plaintext).map_err(|e| TableError::Encryption(e.into()))?;
+        item.insert(field.clone(), value);
+    }
+
+    Ok(())
+}
+
+fn data_key_cipher(data_key: &[u8]) -> Result<Aes256Gcm> {
+    Aes256Gcm::new_from_slice(data_key).map_err(|e| TableError::Encryption(e.into()))
+}
+
+/// Encrypts with a random nonce, which is prepended to the ciphertext.
+fn seal(cipher: &Aes256Gcm, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, aes_gcm::Error> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(
+        &nonce,
+        Payload {
+            msg: plaintext,
+            aad,
+        },
+    )?;
+    Ok([nonce.as_slice(), &ciphertext].concat())
+}
+
+fn open(cipher: &Aes256Gcm, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, aes_gcm::Error> {
+    if sealed.len() < NONCE_LEN {
+        return Err(aes_gcm::Error);
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    cipher.decrypt(
+        Nonce::from_slice(nonce),
+        Payload {
+            msg: ciphertext,
+            aad,
+        },
+    )
+}
+
+fn malformed(what: &str) -> TableError {
+    TableError::Encryption(format!("Malformed encrypted {what}").into())
+}
@@ -0,0 +1,27 @@
+use crate::table::error::Result;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// An item read from a mixed partition, not yet deserialised into a concrete type.
+#[derive(Debug, Clone)]
+pub struct RawEntity {
+    pub(crate) entity_type: Option<String>,
+    pub(crate) item: HashMap<String, AttributeValue>,
+}
+
+impl RawEntity {
+    pub fn entity_type(&self) -> Option<&str> {
+        self.entity_type.as_deref()
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<T> {
+        Ok(serde_dynamo::from_item(self.item)?)
+    }
+}
+
+/// A user-defined enum over the entity types stored in one table.
+pub trait EntitySet: Sized {
+    /// Returns `None` for entity types the set does not cover, which are then skipped.
+    fn from_raw(raw: RawEntity) -> Result<Option<Self>>;
+}
@@ -0,0 +1,140 @@
+use aws_sdk_dynamodb::error::{BuildError, ProvideErrorMetadata, SdkError};
+use std::error::Error;
+use std::fmt;
+
+pub(crate) type BoxError = Box<dyn Error + Send + Sync>;
+pub(crate) type Result<T, E = TableError> = std::result::Result<T, E>;
+
+const THROTTLING_CODES: &[&str] = &[
+    "ProvisionedThroughputExceededException",
+    "RequestLimitExceeded",
+    "ThrottlingException",
+];
+
+/// Error returned by `Table` operations; the underlying SDK error, if any, is the `source`.
+#[derive(Debug)]
+pub enum TableError {
+    NotFound {
+        pk: String,
+        sk: String,
+    },
+    ConditionFailed {
+        pk: String,
+        sk: String,
+        source: Option<BoxError>,
+    },
+    TransactionCanceled {
+        failures: Vec<TransactionFailure>,
+        source: BoxError,
+    },
+    /// Request rate or provisioned throughput exceeded, including batches still unprocessed at
+    /// the batch deadline.
+    Throttled(BoxError),
+    Serialization(serde_dynamo::Error),
+    /// Client-side field encryption or decryption failed, including key provider errors.
+    Encryption(BoxError),
+    /// Offloading attributes to the object store or reading them back failed.
+    Overflow(BoxError),
+    /// Reading or writing a JSON Lines export failed, including malformed lines.
+    Export(BoxError),
+    /// The request was rejected before being sent, e.g. an unknown index.
+    InvalidRequest(BoxError),
+    Service(BoxError),
+}
+
+/// The operation in a cancelled transaction that caused the cancellation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionFailure {
+    pub index: usize,
+    pub pk: String,
+    pub sk: String,
+    pub code: String,
+    pub message: Option<String>,
+}
+
+impl TableError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, TableError::NotFound { .. })
+    }
+
+    pub fn is_condition_failed(&self) -> bool {
+        matches!(self, TableError::ConditionFailed { .. })
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        matches!(self, TableError::Throttled(_))
+    }
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::NotFound { pk, sk } => write!(f, "Item not found for {pk}:{sk}"),
+            TableError::ConditionFailed { pk, sk, .. } => {
+                write!(f, "Condition check failed for {pk}:{sk}")
+            }
+            TableError::TransactionCanceled { failures, .. } => {
+                write!(f, "Transaction canceled")?;
+                for failure in failures {
+                    write!(
+                        f,
+                        "; item {} ({}:{}) failed with {}",
+                        failure.index, failure.pk, failure.sk, failure.code
+                    )?;
+                }
+                Ok(())
+            }
+            TableError::Throttled(e) => write!(f, "Request throttled: {e}"),
+            TableError::Serialization(e) => write!(f, "Serialization failed: {e}"),
+            TableError::Encryption(e) => write!(f, "Encryption failed: {e}"),
+            TableError::Overflow(e) => write!(f, "Object store failed: {e}"),
+            TableError::Export(e) => write!(f, "Export failed: {e}"),
+            TableError::InvalidRequest(e) => write!(f, "Invalid request: {e}"),
+            TableError::Service(e) => write!(f, "Service error: {e}"),
+        }
+    }
+}
+
+impl Error for TableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TableError::NotFound { .. } => None,
+            TableError::ConditionFailed { source, .. } => {
+                source.as_deref().map(|e| e as &(dyn Error + 'static))
+            }
+            TableError::TransactionCanceled { source, .. }
+            | TableError::Throttled(source)
+            | TableError::Encryption(source)
+            | TableError::Overflow(source)
+            | TableError::Export(source)
+            | TableError::InvalidRequest(source)
+            | TableError::Service(source) => Some(source.as_ref()),
+            TableError::Serialization(e) => Some(e),
+        }
+    }
+}
+
+impl<E, R> From<SdkError<E, R>> for TableError
+where
+    E: ProvideErrorMetadata + Error + Send + Sync + 'static,
+    R: fmt::Debug + Send + Sync + 'static,
+{
+    fn from(error: SdkError<E, R>) -> Self {
+        match error.code() {
+            Some(code) if THROTTLING_CODES.contains(&code) => TableError::Throttled(error.into()),
+            _ => TableError::Service(error.into()),
+        }
+    }
+}
+
+impl From<serde_dynamo::Error> for TableError {
+    fn from(error: serde_dynamo::Error) -> Self {
+        TableError::Serialization(error)
+    }
+}
+
+impl From<BuildError> for TableError {
+    fn from(error: BuildError) -> Self {
+        TableError::InvalidRequest(error.into())
+    }
+}
@@ -0,0 +1,270 @@
+use crate::table::schema::KeySchema;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::{HashMap, HashSet};
+
+pub trait IntoAttribute {
+    fn into_attribute(self) -> AttributeValue;
+}
+
+impl IntoAttribute for AttributeValue {
+    fn into_attribute(self) -> AttributeValue {
+        self
+    }
+}
+
+impl IntoAttribute for String {
+    fn into_attribute(self) -> AttributeValue {
+        AttributeValue::S(self)
+    }
+}
+
+impl IntoAttribute for &str {
+    fn into_attribute(self) -> AttributeValue {
+        AttributeValue::S(self.to_owned())
+    }
+}
+
+impl IntoAttribute for bool {
+    fn into_attribute(self) -> AttributeValue {
+        AttributeValue::Bool(self)
+    }
+}
+
+impl IntoAttribute for HashSet<String> {
+    fn into_attribute(self) -> AttributeValue {
+        AttributeValue::Ss(self.into_iter().collect())
+    }
+}
+
+macro_rules! impl_into_number_attribute {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoAttribute for $ty {
+                fn into_attribute(self) -> AttributeValue {
+                    AttributeValue::N(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_number_attribute!(i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    ItemExists,
+    ItemNotExists,
+    AttributeExists(String),
+    AttributeNotExists(String),
+    Equals(String, AttributeValue),
+    NotEquals(String, AttributeValue),
+    LessThan(String, AttributeValue),
+    LessThanOrEqual(String, AttributeValue),
+    GreaterThan(String, AttributeValue),
+    GreaterThanOrEqual(String, AttributeValue),
+    BeginsWith(String, String),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn equals(attribute: &str, value: impl IntoAttribute) -> Self {
+        Condition::Equals(attribute.to_owned(), value.into_attribute())
+    }
+
+    /// Optimistic locking check against a numeric version attribute.
+    pub fn version_equals(attribute: &str, version: u64) -> Self {
+        Condition::equals(attribute, version)
+    }
+
+    pub(crate) fn render(&self, placeholders: &mut Placeholders) -> String {
+        let compare = |placeholders: &mut Placeholders, attribute, operator, value| {
+            let name = placeholders.name(attribute);
+            let value = placeholders.value(value);
+            format!("{name} {operator} {value}")
+        };
+
+        match self {
+            Condition::ItemExists => {
+                let partition_key = placeholders.partition_key.clone();
+                format!("attribute_exists({})", placeholders.name(&partition_key))
+            }
+            Condition::ItemNotExists => {
+                let partition_key = placeholders.partition_key.clone();
+                format!(
+                    "attribute_not_exists({})",
+                    placeholders.name(&partition_key)
+                )
+            }
+            Condition::AttributeExists(attribute) => {
+                format!("attribute_exists({})", placeholders.name(attribute))
+            }
+            Condition::AttributeNotExists(attribute) => {
+                format!("attribute_not_exists({})", placeholders.name(attribute))
+            }
+            Condition::Equals(attribute, value) => compare(placeholders, attribute, "=", value),
+            Condition::NotEquals(attribute, value) => compare(placeholders, attribute, "<>", value),
+            Condition::LessThan(attribute, value) => compare(placeholders, attribute, "<", value),
+            Condition::LessThanOrEqual(attribute, value) => {
+                compare(placeholders, attribute, "<=", value)
+            }
+            Condition::GreaterThan(attribute, value) => {
+                compare(placeholders, attribute, ">", value)
+            }
+            Condition::GreaterThanOrEqual(attribute, value) => {
+                compare(placeholders, attribute, ">=", value)
+            }
+            Condition::BeginsWith(attribute, prefix) => {
+                let name = placeholders.name(attribute);
+                let value = placeholders.value(&AttributeValue::S(prefix.clone()));
+                format!("begins_with({name}, {value})")
+            }
+            Condition::And(conditions) => join(conditions, " AND ", placeholders),
+            Condition::Or(conditions) => join(conditions, " OR ", placeholders),
+        }
+    }
+}
+
+fn join(conditions: &[Condition], separator: &str, placeholders: &mut Placeholders) -> String {
+    let rendered = conditions
+        .iter()
+        .map(|condition| format!("({})", condition.render(placeholders)))
+        .collect::<Vec<_>>();
+    rendered.join(separator)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateAction {
+    Set(String, AttributeValue),
+    Remove(String),
+    Add(String, AttributeValue),
+    Delete(String, AttributeValue),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Update {
+    pub(crate) actions: Vec<UpdateAction>,
+    pub(crate) condition: Option<Condition>,
+}
+
+impl Update {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, attribute: &str, value: impl IntoAttribute) -> Self {
+        self.actions.push(UpdateAction::Set(
+            attribute.to_owned(),
+            value.into_attribute(),
+        ));
+        self
+    }
+
+    pub fn remove(mut self, attribute: &str) -> Self {
+        self.actions
+            .push(UpdateAction::Remove(attribute.to_owned()));
+        self
+    }
+
+    /// Adds to a number attribute, or unions into a set attribute.
+    pub fn add(mut self, attribute: &str, value: impl IntoAttribute) -> Self {
+        self.actions.push(UpdateAction::Add(
+            attribute.to_owned(),
+            value.into_attribute(),
+        ));
+        self
+    }
+
+    /// Removes elements from a set attribute.
+    pub fn delete(mut self, attribute: &str, value: impl IntoAttribute) -> Self {
+        self.actions.push(UpdateAction::Delete(
+            attribute.to_owned(),
+            value.into_attribute(),
+        ));
+        self
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub(crate) fn render(&self, placeholders: &mut Placeholders) -> String {
+        let mut sets = Vec::new();
+        let mut removes = Vec::new();
+        let mut adds = Vec::new();
+        let mut deletes = Vec::new();
+
+        for action in &self.actions {
+            match action {
+                UpdateAction::Set(attribute, value) => {
+                    let name = placeholders.name(attribute);
+                    sets.push(format!("{name} = {}", placeholders.value(value)));
+                }
+                UpdateAction::Remove(attribute) => removes.push(placeholders.name(attribute)),
+                UpdateAction::Add(attribute, value) => {
+                    let name = placeholders.name(attribute);
+                    adds.push(format!("{name} {}", placeholders.value(value)));
+                }
+                UpdateAction::Delete(attribute, value) => {
+                    let name = placeholders.name(attribute);
+                    deletes.push(format!("{name} {}", placeholders.value(value)));
+                }
+            }
+        }
+
+        [
+            ("SET", sets),
+            ("REMOVE", removes),
+            ("ADD", adds),
+            ("DELETE", deletes),
+        ]
+        .into_iter()
+        .filter(|(_, clauses)| !clauses.is_empty())
+        .map(|(keyword, clauses)| format!("{keyword} {}", clauses.join(", ")))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// Collects the `#name` and `:value` substitutions of a rendered expression.
+#[derive(Debug)]
+pub(crate) struct Placeholders {
+    pub(crate) partition_key: String,
+    pub(crate) names: HashMap<String, String>,
+    pub(crate) values: HashMap<String, AttributeValue>,
+}
+
+impl Placeholders {
+    pub(crate) fn new(key_schema: &KeySchema) -> Self {
+        Self {
+            partition_key: key_schema.partition_key.name.clone(),
+            names: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn name(&mut self, attribute: &str) -> String {
+        if let Some((placeholder, _)) = self.names.iter().find(|(_, name)| *name == attribute) {
+            return placeholder.clone();
+        }
+
+        let placeholder = format!("#n{}", self.names.len());
+        self.names.insert(placeholder.clone(), attribute.to_owned());
+        placeholder
+    }
+
+    pub(crate) fn value(&mut self, value: &AttributeValue) -> String {
+        let placeholder = format!(":v{}", self.values.len());
+        self.values.insert(placeholder.clone(), value.clone());
+        placeholder
+    }
+
+    pub(crate) fn names(&self) -> Option<HashMap<String, String>> {
+        (!self.names.is_empty()).then(|| self.names.clone())
+    }
+
+    pub(crate) fn values(&self) -> Option<HashMap<String, AttributeValue>> {
+        (!self.values.is_empty()).then(|| self.values.clone())
+    }
+}
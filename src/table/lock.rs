@@ -0,0 +1,315 @@
+use crate::Table;
+use crate::model::Keyed;
+use crate::table::error::{Result, TableError};
+use crate::table::expression::{Condition, IntoAttribute, Update};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(30);
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+const LOCK_SK: &str = "LOCK";
+
+const OWNER: &str = "owner";
+const LEASE_EXPIRES_AT: &str = "lease_expires_at";
+const FENCING_TOKEN: &str = "fencing_token";
+
+static ACQUISITIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Lock state stored through any `Table<LockLease>`; the item outlives releases so fencing
+/// tokens keep increasing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockLease {
+    pub name: String,
+    pub owner: String,
+    /// Milliseconds since the Unix epoch.
+    pub lease_expires_at: u64,
+    pub fencing_token: u64,
+}
+
+impl Keyed for LockLease {
+    fn pk(&self) -> String {
+        lock_pk(&self.name)
+    }
+
+    fn sk(&self) -> String {
+        LOCK_SK.to_owned()
+    }
+}
+
+fn lock_pk(name: &str) -> String {
+    format!("LOCK#{name}")
+}
+
+/// Lease-based mutual exclusion across processes, kept alive by a background heartbeat.
+///
+/// Expiry is judged by each holder's clock, so leases should be long relative to clock skew.
+/// Pass `LockGuard::fencing_token` to the guarded resource so writes from a holder whose lease
+/// lapsed can be rejected.
+pub struct DistributedLock {
+    table: Arc<dyn Table<LockLease>>,
+    name: String,
+    owner: String,
+    lease_duration: Duration,
+    heartbeat_interval: Duration,
+    retry_interval: Duration,
+}
+
+impl DistributedLock {
+    pub fn builder() -> DistributedLockBuilder {
+        DistributedLockBuilder::default()
+    }
+
+    /// Takes the lock if it is free or its lease has expired, otherwise returns `None`.
+    pub async fn try_acquire(&self) -> Result<Option<LockGuard>> {
+        let now = now_millis()?;
+        let owner = format!(
+            "{}#{}",
+            self.owner,
+            ACQUISITIONS.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let update = Update::new()
+            .set("name", self.name.as_str())
+            .set(OWNER, owner.as_str())
+            .set(
+                LEASE_EXPIRES_AT,
+                now + self.lease_duration.as_millis() as u64,
+            )
+            .add(FENCING_TOKEN, 1u64)
+            .condition(Condition::Or(vec![
+                Condition::ItemNotExists,
+                Condition::LessThan(LEASE_EXPIRES_AT.to_owned(), now.into_attribute()),
+            ]));
+
+        let lease = match self
+            .table
+            .update_entry(&lock_pk(&self.name), LOCK_SK, &update)
+            .await
+        {
+            Ok(lease) => lease,
+            Err(TableError::ConditionFailed { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let held = Arc::new(AtomicBool::new(true));
+        let heartbeat = tokio::spawn(heartbeat(
+            self.table.clone(),
+            lease.clone(),
+            self.lease_duration,
+            self.heartbeat_interval,
+            held.clone(),
+        ));
+
+        Ok(Some(LockGuard {
+            table: self.table.clone(),
+            lease,
+            held,
+            heartbeat,
+            released: false,
+        }))
+    }
+
+    /// Waits until the lock is acquired; wrap in `tokio::time::timeout` to bound the wait.
+    pub async fn acquire(&self) -> Result<LockGuard> {
+        loop {
+            if let Some(guard) = self.try_acquire().await? {
+                return Ok(guard);
+            }
+            tokio::time::sleep(self.retry_interval).await;
+        }
+    }
+}
+
+/// A held lock; released on `release` or, in the background, on drop.
+pub struct LockGuard {
+    table: Arc<dyn Table<LockLease>>,
+    lease: LockLease,
+    held: Arc<AtomicBool>,
+    heartbeat: JoinHandle<()>,
+    released: bool,
+}
+
+impl LockGuard {
+    /// Strictly greater than the token of every earlier holder of the lock.
+    pub fn fencing_token(&self) -> u64 {
+        self.lease.fencing_token
+    }
+
+    /// `false` once a renewal failed to extend the lease before it expired, or another holder
+    /// took over.
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::Acquire)
+    }
+
+    pub async fn release(mut self) -> Result<()> {
+        self.released = true;
+        self.heartbeat.abort();
+        self.held.store(false, Ordering::Release);
+        release(self.table.as_ref(), &self.lease).await
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        self.heartbeat.abort();
+        self.held.store(false, Ordering::Release);
+
+        // Without a runtime the lease is left to expire.
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let table = self.table.clone();
+            let lease = self.lease.clone();
+            runtime.spawn(async move {
+                if let Err(e) = release(table.as_ref(), &lease).await {
+                    warn!("Failed to release lock {}: {e}", lease.name);
+                }
+            });
+        }
+    }
+}
+
+/// Condition that the lease is still the one taken with `lease`.
+fn still_held(lease: &LockLease) -> Condition {
+    Condition::And(vec![
+        Condition::equals(OWNER, lease.owner.as_str()),
+        Condition::equals(FENCING_TOKEN, lease.fencing_token),
+    ])
+}
+
+/// Expires the lease rather than deleting the item, keeping the fencing token.
+async fn release(table: &dyn Table<LockLease>, lease: &LockLease) -> Result<()> {
+    let update = Update::new()
+        .set(LEASE_EXPIRES_AT, 0u64)
+        .condition(still_held(lease));
+
+    match table.update_entry(&lease.pk(), LOCK_SK, &update).await {
+        Ok(_) | Err(TableError::ConditionFailed { .. }) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+async fn heartbeat(
+    table: Arc<dyn Table<LockLease>>,
+    lease: LockLease,
+    lease_duration: Duration,
+    interval: Duration,
+    held: Arc<AtomicBool>,
+) {
+    let mut expires_at = lease.lease_expires_at;
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let renewal = match now_millis() {
+            Ok(now) => now + lease_duration.as_millis() as u64,
+            Err(e) => {
+                warn!("Failed to renew lock {}: {e}", lease.name);
+                continue;
+            }
+        };
+        let update = Update::new()
+            .set(LEASE_EXPIRES_AT, renewal)
+            .condition(still_held(&lease));
+
+        match table.update_entry(&lease.pk(), LOCK_SK, &update).await {
+            Ok(_) => expires_at = renewal,
+            Err(TableError::ConditionFailed { .. }) => {
+                warn!("Lost lock {} to another holder", lease.name);
+                break;
+            }
+            Err(e) => {
+                warn!("Failed to renew lock {}: {e}", lease.name);
+                if now_millis().is_ok_and(|now| now >= expires_at) {
+                    warn!("Lease on lock {} expired", lease.name);
+                    break;
+                }
+            }
+        }
+    }
+
+    held.store(false, Ordering::Release);
+}
+
+fn now_millis() -> Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| TableError::Service(e.into()))?;
+    Ok(now.as_millis() as u64)
+}
+
+#[derive(Default)]
+pub struct DistributedLockBuilder {
+    table: Option<Arc<dyn Table<LockLease>>>,
+    name: Option<String>,
+    owner: Option<String>,
+    lease_duration: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    retry_interval: Option<Duration>,
+}
+
+impl DistributedLockBuilder {
+    /// Usually a `DynamoDbClient`.
+    pub fn table(mut self, table: Arc<dyn Table<LockLease>>) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Identifies this process in the lock item, e.g. the instance ID; defaults to the process ID.
+    pub fn owner(mut self, owner: &str) -> Self {
+        self.owner = Some(owner.to_owned());
+        self
+    }
+
+    /// How long a lease lasts without renewal, 30 seconds by default.
+    pub fn lease_duration(mut self, lease_duration: Duration) -> Self {
+        self.lease_duration = Some(lease_duration);
+        self
+    }
+
+    /// How often the lease is renewed, a third of the lease duration by default.
+    pub fn heartbeat_interval(mut self, heartbeat_interval: Duration) -> Self {
+        self.heartbeat_interval = Some(heartbeat_interval);
+        self
+    }
+
+    /// How long `acquire` waits between attempts, 1 second by default.
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = Some(retry_interval);
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<DistributedLock> {
+        let table = self.table.ok_or(anyhow!("Missing lock table"))?;
+        let name = self.name.ok_or(anyhow!("Missing lock name"))?;
+        let lease_duration = self.lease_duration.unwrap_or(DEFAULT_LEASE_DURATION);
+        let heartbeat_interval = self.heartbeat_interval.unwrap_or(lease_duration / 3);
+
+        if heartbeat_interval >= lease_duration {
+            return Err(anyhow!("Heartbeat interval must be shorter than the lease"));
+        }
+
+        Ok(DistributedLock {
+            table,
+            name,
+            owner: self
+                .owner
+                .unwrap_or_else(|| format!("pid-{}", std::process::id())),
+            lease_duration,
+            heartbeat_interval,
+            retry_interval: self.retry_interval.unwrap_or(DEFAULT_RETRY_INTERVAL),
+        })
+    }
+}
@@ -0,0 +1,488 @@
+use crate::Table;
+use crate::table::Keyed;
+use crate::table::error::{Result, TableError};
+use crate::table::expression::{Condition, Update, UpdateAction};
+use crate::table::query::{Cursor, Page, Query, SortKeyCondition};
+use crate::table::schema::Index;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Serialize, de::DeserializeOwned};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+type Item = HashMap<String, AttributeValue>;
+
+const PK: &str = "PK";
+const SK: &str = "SK";
+
+/// `Table` backed by a sorted map, for tests and local runs without DynamoDB.
+pub struct InMemoryTable<T> {
+    partitions: RwLock<BTreeMap<String, BTreeMap<String, Item>>>,
+    indexes: HashMap<String, Index>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for InMemoryTable<T> {
+    fn default() -> Self {
+        Self {
+            partitions: RwLock::default(),
+            indexes: HashMap::new(),
+            _entry: PhantomData,
+        }
+    }
+}
+
+impl<T> InMemoryTable<T>
+where
+    T: Serialize + DeserializeOwned + Keyed,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn index(mut self, index: Index) -> Self {
+        self.indexes.insert(index.name.clone(), index);
+        self
+    }
+
+    fn read(&self) -> Result<RwLockReadGuard<'_, BTreeMap<String, BTreeMap<String, Item>>>> {
+        self.partitions
+            .read()
+            .map_err(|_| TableError::Service("In-memory table lock poisoned".into()))
+    }
+
+    fn write(&self) -> Result<RwLockWriteGuard<'_, BTreeMap<String, BTreeMap<String, Item>>>> {
+        self.partitions
+            .write()
+            .map_err(|_| TableError::Service("In-memory table lock poisoned".into()))
+    }
+
+    fn key(pk: &str, sk: &str) -> Item {
+        HashMap::from([
+            (PK.to_owned(), AttributeValue::S(pk.to_owned())),
+            (SK.to_owned(), AttributeValue::S(sk.to_owned())),
+        ])
+    }
+
+    fn item_map(item: &T) -> Result<Item> {
+        let mut item_map: Item = serde_dynamo::to_item(item)?;
+        item_map.extend(
+            item.index_keys()
+                .into_iter()
+                .map(|(attribute, value)| (attribute, AttributeValue::S(value))),
+        );
+        item_map.extend(Self::key(&item.pk(), &item.sk()));
+        Ok(item_map)
+    }
+
+    fn put_item(&self, item: &T, condition: Option<&Condition>) -> Result<()> {
+        let (pk, sk) = (item.pk(), item.sk());
+        let item_map = Self::item_map(item)?;
+        let mut partitions = self.write()?;
+
+        let existing = partitions.get(&pk).and_then(|partition| partition.get(&sk));
+        check_condition(condition, existing, &pk, &sk)?;
+
+        partitions.entry(pk).or_default().insert(sk, item_map);
+        Ok(())
+    }
+
+    fn delete_item(&self, pk: &str, sk: &str, condition: Option<&Condition>) -> Result<()> {
+        let mut partitions = self.write()?;
+
+        let existing = partitions.get(pk).and_then(|partition| partition.get(sk));
+        check_condition(condition, existing, pk, sk)?;
+
+        if let Some(partition) = partitions.get_mut(pk) {
+            partition.remove(sk);
+            if partition.is_empty() {
+                partitions.remove(pk);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies `update`, returning what `result` reads from the updated item; nothing is stored
+    /// if `result` fails.
+    fn update_item<R>(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        result: impl FnOnce(&Item) -> Result<R>,
+    ) -> Result<R> {
+        let mut partitions = self.write()?;
+
+        let existing = partitions.get(pk).and_then(|partition| partition.get(sk));
+        check_condition(update.condition.as_ref(), existing, pk, sk)?;
+
+        let mut item = existing.cloned().unwrap_or_else(|| Self::key(pk, sk));
+        apply_update(update, &mut item)?;
+        let result = result(&item)?;
+
+        partitions
+            .entry(pk.to_owned())
+            .or_default()
+            .insert(sk.to_owned(), item);
+        Ok(result)
+    }
+
+    /// Returns the items matching the query's key conditions, in key order.
+    fn matching_items(&self, query: &Query) -> Result<Vec<Item>> {
+        let partitions = self.read()?;
+
+        let (partition_key, sort_key) = match &query.index {
+            Some(index_name) => {
+                let index = self.indexes.get(index_name).ok_or_else(|| {
+                    TableError::InvalidRequest(format!("Unknown index {index_name}").into())
+                })?;
+                (
+                    index.key_schema.partition_key.name.as_str(),
+                    index
+                        .key_schema
+                        .sort_key
+                        .as_ref()
+                        .map(|key| key.name.as_str()),
+                )
+            }
+            None => (PK, Some(SK)),
+        };
+
+        if query.sort_key.is_some() && sort_key.is_none() {
+            return Err(TableError::InvalidRequest(
+                "Sort key condition on a key without a sort key".into(),
+            ));
+        }
+
+        let mut items = partitions
+            .values()
+            .flat_map(BTreeMap::values)
+            .filter(|item| string_attribute(item, partition_key) == Some(query.pk.as_str()))
+            .filter(|item| {
+                let Some(condition) = &query.sort_key else {
+                    return true;
+                };
+                sort_key
+                    .and_then(|sort_key| string_attribute(item, sort_key))
+                    .is_some_and(|value| sort_key_matches(condition, value))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        items.sort_by(|a, b| {
+            let sort_value = |item| sort_key.and_then(|sort_key| string_attribute(item, sort_key));
+            sort_value(a)
+                .cmp(&sort_value(b))
+                .then_with(|| string_attribute(a, PK).cmp(&string_attribute(b, PK)))
+                .then_with(|| string_attribute(a, SK).cmp(&string_attribute(b, SK)))
+        });
+
+        if !query.scan_forward {
+            items.reverse();
+        }
+
+        Ok(items)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> Table<T> for InMemoryTable<T>
+where
+    T: Serialize + DeserializeOwned + Keyed + Send + Sync + 'static,
+{
+    async fn get_entry(&self, pk: &str, sk: &str) -> Result<T> {
+        let partitions = self.read()?;
+        let item = partitions
+            .get(pk)
+            .and_then(|partition| partition.get(sk))
+            .ok_or_else(|| TableError::NotFound {
+                pk: pk.to_owned(),
+                sk: sk.to_owned(),
+            })?;
+        let result = serde_dynamo::from_item(item.clone())?;
+        Ok(result)
+    }
+
+    async fn find_projection<P>(&self, pk: &str, sk: &str, attributes: &[&str]) -> Result<Option<P>>
+    where
+        P: DeserializeOwned + Send,
+    {
+        let partitions = self.read()?;
+        let Some(item) = partitions.get(pk).and_then(|partition| partition.get(sk)) else {
+            return Ok(None);
+        };
+
+        let projected: Item = item
+            .iter()
+            .filter(|(name, _)| attributes.contains(&name.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        Ok(Some(serde_dynamo::from_item(projected)?))
+    }
+
+    async fn put_entry(&self, item: T) -> Result<()> {
+        self.put_item(&item, None)
+    }
+
+    async fn put_entry_if(&self, item: T, condition: &Condition) -> Result<()> {
+        self.put_item(&item, Some(condition))
+    }
+
+    async fn delete_entry(&self, pk: &str, sk: &str) -> Result<()> {
+        self.delete_item(pk, sk, None)
+    }
+
+    async fn delete_entry_if(&self, pk: &str, sk: &str, condition: &Condition) -> Result<()> {
+        self.delete_item(pk, sk, Some(condition))
+    }
+
+    async fn update_entry(&self, pk: &str, sk: &str, update: &Update) -> Result<T> {
+        self.update_item(pk, sk, update, |item| {
+            serde_dynamo::from_item(item.clone()).map_err(Into::into)
+        })
+    }
+
+    async fn update_attribute(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        attribute: &str,
+    ) -> Result<Option<AttributeValue>> {
+        self.update_item(pk, sk, update, |item| Ok(item.get(attribute).cloned()))
+    }
+
+    async fn batch_get_entries(&self, keys: &[(&str, &str)]) -> Result<Vec<T>> {
+        let partitions = self.read()?;
+
+        keys.iter()
+            .filter_map(|(pk, sk)| partitions.get(*pk)?.get(*sk))
+            .map(|item| serde_dynamo::from_item(item.clone()).map_err(Into::into))
+            .collect()
+    }
+
+    async fn batch_put_entries(&self, items: Vec<T>) -> Result<()> {
+        for item in &items {
+            self.put_item(item, None)?;
+        }
+        Ok(())
+    }
+
+    async fn query(&self, query: &Query) -> Result<Page<T>> {
+        let items = self.matching_items(query)?;
+
+        let start = match &query.cursor {
+            Some(Cursor(last_key)) => items
+                .iter()
+                .position(|item| {
+                    item.get(PK) == last_key.get(PK) && item.get(SK) == last_key.get(SK)
+                })
+                .map_or(items.len(), |position| position + 1),
+            None => 0,
+        };
+        let end = match query.limit {
+            Some(limit) => (start + limit.max(0) as usize).min(items.len()),
+            None => items.len(),
+        };
+
+        let page = &items[start..end];
+        let cursor = (end < items.len())
+            .then(|| page.last())
+            .flatten()
+            .map(|last| {
+                Cursor(
+                    [PK, SK]
+                        .into_iter()
+                        .filter_map(|key| Some((key.to_owned(), last.get(key)?.clone())))
+                        .collect(),
+                )
+            });
+
+        let items = page
+            .iter()
+            .map(|item| serde_dynamo::from_item(item.clone()))
+            .collect::<Result<Vec<T>, _>>()?;
+
+        Ok(Page { items, cursor })
+    }
+}
+
+fn string_attribute<'a>(item: &'a Item, attribute: &str) -> Option<&'a str> {
+    match item.get(attribute) {
+        Some(AttributeValue::S(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn sort_key_matches(condition: &SortKeyCondition, value: &str) -> bool {
+    match condition {
+        SortKeyCondition::Equals(other) => value == other,
+        SortKeyCondition::BeginsWith(prefix) => value.starts_with(prefix.as_str()),
+        SortKeyCondition::Between(low, high) => low.as_str() <= value && value <= high.as_str(),
+        SortKeyCondition::LessThan(other) => value < other.as_str(),
+        SortKeyCondition::LessThanOrEqual(other) => value <= other.as_str(),
+        SortKeyCondition::GreaterThan(other) => value > other.as_str(),
+        SortKeyCondition::GreaterThanOrEqual(other) => value >= other.as_str(),
+    }
+}
+
+fn check_condition(
+    condition: Option<&Condition>,
+    existing: Option<&Item>,
+    pk: &str,
+    sk: &str,
+) -> Result<()> {
+    match condition {
+        Some(condition) if !evaluate(condition, existing) => Err(TableError::ConditionFailed {
+            pk: pk.to_owned(),
+            sk: sk.to_owned(),
+            source: None,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn evaluate(condition: &Condition, item: Option<&Item>) -> bool {
+    let attribute = |name: &str| item.and_then(|item| item.get(name));
+    let compare = |name: &str, value: &AttributeValue| {
+        attribute(name).and_then(|current| compare_attributes(current, value))
+    };
+
+    match condition {
+        Condition::ItemExists => item.is_some(),
+        Condition::ItemNotExists => item.is_none(),
+        Condition::AttributeExists(name) => attribute(name).is_some(),
+        Condition::AttributeNotExists(name) => attribute(name).is_none(),
+        Condition::Equals(name, value) => {
+            attribute(name) == Some(value) || compare(name, value) == Some(Ordering::Equal)
+        }
+        Condition::NotEquals(name, value) => {
+            !(attribute(name) == Some(value) || compare(name, value) == Some(Ordering::Equal))
+        }
+        Condition::LessThan(name, value) => compare(name, value).is_some_and(Ordering::is_lt),
+        Condition::LessThanOrEqual(name, value) => {
+            compare(name, value).is_some_and(Ordering::is_le)
+        }
+        Condition::GreaterThan(name, value) => compare(name, value).is_some_and(Ordering::is_gt),
+        Condition::GreaterThanOrEqual(name, value) => {
+            compare(name, value).is_some_and(Ordering::is_ge)
+        }
+        Condition::BeginsWith(name, prefix) => matches!(
+            attribute(name),
+            Some(AttributeValue::S(value)) if value.starts_with(prefix.as_str())
+        ),
+        Condition::And(conditions) => conditions.iter().all(|c| evaluate(c, item)),
+        Condition::Or(conditions) => conditions.iter().any(|c| evaluate(c, item)),
+    }
+}
+
+fn compare_attributes(left: &AttributeValue, right: &AttributeValue) -> Option<Ordering> {
+    match (left, right) {
+        (AttributeValue::S(left), AttributeValue::S(right)) => Some(left.cmp(right)),
+        (AttributeValue::N(left), AttributeValue::N(right)) => left
+            .parse::<f64>()
+            .ok()?
+            .partial_cmp(&right.parse::<f64>().ok()?),
+        (AttributeValue::B(left), AttributeValue::B(right)) => {
+            Some(left.as_ref().cmp(right.as_ref()))
+        }
+        _ => None,
+    }
+}
+
+fn apply_update(update: &Update, item: &mut Item) -> Result<()> {
+    for action in &update.actions {
+        match action {
+            UpdateAction::Set(name, value) => {
+                item.insert(name.clone(), value.clone());
+            }
+            UpdateAction::Remove(name) => {
+                item.remove(name);
+            }
+            UpdateAction::Add(name, value) => {
+                let updated = match (item.get(name), value) {
+                    (None, value) => value.clone(),
+                    (Some(AttributeValue::N(current)), AttributeValue::N(delta)) => {
+                        AttributeValue::N(add_numbers(current, delta)?)
+                    }
+                    (Some(AttributeValue::Ss(current)), AttributeValue::Ss(values)) => {
+                        AttributeValue::Ss(union(current, values))
+                    }
+                    (Some(AttributeValue::Ns(current)), AttributeValue::Ns(values)) => {
+                        AttributeValue::Ns(union(current, values))
+                    }
+                    _ => {
+                        return Err(invalid_update(format!(
+                            "ADD on {name} requires a number or set of matching type"
+                        )));
+                    }
+                };
+                item.insert(name.clone(), updated);
+            }
+            UpdateAction::Delete(name, value) => {
+                let updated = match (item.get(name), value) {
+                    (None, _) => None,
+                    (Some(AttributeValue::Ss(current)), AttributeValue::Ss(values)) => {
+                        Some(AttributeValue::Ss(difference(current, values)))
+                    }
+                    (Some(AttributeValue::Ns(current)), AttributeValue::Ns(values)) => {
+                        Some(AttributeValue::Ns(difference(current, values)))
+                    }
+                    _ => {
+                        return Err(invalid_update(format!(
+                            "DELETE on {name} requires a set of matching type"
+                        )));
+                    }
+                };
+                match updated {
+                    Some(AttributeValue::Ss(values) | AttributeValue::Ns(values))
+                        if values.is_empty() =>
+                    {
+                        item.remove(name);
+                    }
+                    Some(updated) => {
+                        item.insert(name.clone(), updated);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_numbers(current: &str, delta: &str) -> Result<String> {
+    if let (Ok(current), Ok(delta)) = (current.parse::<i128>(), delta.parse::<i128>()) {
+        return Ok((current + delta).to_string());
+    }
+    let parse = |value: &str| {
+        value
+            .parse::<f64>()
+            .map_err(|_| invalid_update(format!("{value} is not a number")))
+    };
+    Ok((parse(current)? + parse(delta)?).to_string())
+}
+
+fn invalid_update(message: String) -> TableError {
+    TableError::InvalidRequest(message.into())
+}
+
+fn union(current: &[String], values: &[String]) -> Vec<String> {
+    let mut result = current.to_vec();
+    for value in values {
+        if !result.contains(value) {
+            result.push(value.clone());
+        }
+    }
+    result
+}
+
+fn difference(current: &[String], values: &[String]) -> Vec<String> {
+    current
+        .iter()
+        .filter(|value| !values.contains(value))
+        .cloned()
+        .collect()
+}
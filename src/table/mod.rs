@@ -1,9 +1,46 @@
+mod admin;
+mod cache;
+#[cfg(any(feature = "table-encryption", feature = "table-overflow"))]
+mod codec;
 mod dynamo_db;
-pub use dynamo_db::DynamoDbClient;
+#[cfg(feature = "table-encryption")]
+mod encryption;
+mod entity;
+mod error;
+mod expression;
+mod lock;
+#[cfg(feature = "in-memory-table")]
+mod memory;
+#[cfg(feature = "table-overflow")]
+mod overflow;
+mod query;
+mod schema;
+#[cfg(feature = "table-stream")]
+mod stream;
+
+pub use admin::{BillingMode, SchemaDifference, TableAdmin};
+pub use cache::{CachedTable, CachedTableBuilder};
+pub use dynamo_db::{DynamoDbClient, Transaction};
+#[cfg(feature = "table-encryption")]
+pub use encryption::{DataKey, KeyProvider, LocalKeyProvider};
+pub use entity::{EntitySet, RawEntity};
+pub use error::{TableError, TransactionFailure};
+pub use expression::{Condition, IntoAttribute, Update, UpdateAction};
+pub use lock::{DistributedLock, DistributedLockBuilder, LockGuard, LockLease};
+#[cfg(feature = "in-memory-table")]
+pub use memory::InMemoryTable;
+#[cfg(feature = "table-overflow")]
+pub use overflow::{InMemoryObjectStore, LocalObjectStore, ObjectStore};
+pub use query::{Cursor, Page, Query, Scan, SortKeyCondition};
+pub use schema::{Index, KeyAttribute, KeySchema, KeyType, TrackingAttributes};
+#[cfg(feature = "table-stream")]
+pub use stream::{ChangeEvent, ShardCheckpoint, StreamConsumer, StreamEvent};
 
 use crate::model::Keyed;
-use anyhow::Result;
+use aws_sdk_dynamodb::types::AttributeValue;
+use error::Result;
 use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashSet;
 
 #[async_trait::async_trait]
 pub trait Table<T>: Send + Sync
@@ -11,6 +48,141 @@ where
     T: Serialize + DeserializeOwned + Keyed + Send + Sync,
 {
     async fn get_entry(&self, pk: &str, sk: &str) -> Result<T>;
+
+    /// Fetches only the given top-level attributes, deserialised into `P`.
+    async fn find_projection<P>(
+        &self,
+        pk: &str,
+        sk: &str,
+        attributes: &[&str],
+    ) -> Result<Option<P>>
+    where
+        Self: Sized,
+        P: DeserializeOwned + Send;
+
     async fn put_entry(&self, item: T) -> Result<()>;
-    async fn get_entries_by_pk(&self, pk: &str) -> Result<Vec<T>>;
+    async fn put_entry_if(&self, item: T, condition: &Condition) -> Result<()>;
+    async fn delete_entry(&self, pk: &str, sk: &str) -> Result<()>;
+    async fn delete_entry_if(&self, pk: &str, sk: &str, condition: &Condition) -> Result<()>;
+
+    /// Applies a partial update and returns the item as it is after the update.
+    async fn update_entry(&self, pk: &str, sk: &str, update: &Update) -> Result<T>;
+
+    /// Applies a partial update and returns the new value of `attribute`, `None` if it is unset.
+    async fn update_attribute(
+        &self,
+        pk: &str,
+        sk: &str,
+        update: &Update,
+        attribute: &str,
+    ) -> Result<Option<AttributeValue>>;
+
+    /// Fetches the entries that exist for the given `(pk, sk)` pairs; missing keys are skipped.
+    async fn batch_get_entries(&self, keys: &[(&str, &str)]) -> Result<Vec<T>>;
+    async fn batch_put_entries(&self, items: Vec<T>) -> Result<()>;
+
+    /// Fetches a single page of results, along with a cursor if more remain.
+    async fn query(&self, query: &Query) -> Result<Page<T>>;
+
+    /// Follows cursors until the partition is exhausted or the query limit is reached.
+    async fn query_all(&self, query: &Query) -> Result<Vec<T>> {
+        let mut query = query.clone();
+        let limit = query.limit;
+        let mut results = Vec::new();
+
+        loop {
+            if let Some(limit) = limit {
+                query.limit = Some(limit - results.len() as i32);
+            }
+
+            let page = self.query(&query).await?;
+            results.extend(page.items);
+
+            let limit_reached = limit.is_some_and(|limit| results.len() as i32 >= limit);
+            match page.cursor {
+                Some(cursor) if !limit_reached => query.cursor = Some(cursor),
+                _ => break,
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Like `get_entry`, but a missing item is `None` rather than `TableError::NotFound`.
+    async fn find_entry(&self, pk: &str, sk: &str) -> Result<Option<T>> {
+        match self.get_entry(pk, sk).await {
+            Ok(item) => Ok(Some(item)),
+            Err(TableError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Atomically adds `delta` to a number attribute, counting from zero if it is unset, and
+    /// returns the new value. A missing item is created with just its key.
+    async fn increment(&self, pk: &str, sk: &str, attribute: &str, delta: i64) -> Result<i64> {
+        let update = Update::new().add(attribute, delta);
+        match self.update_attribute(pk, sk, &update, attribute).await? {
+            Some(AttributeValue::N(value)) => value
+                .parse()
+                .map_err(|e| TableError::Service(format!("{attribute} on {pk}:{sk}: {e}").into())),
+            _ => Err(unexpected_type(pk, sk, attribute, "an integer")),
+        }
+    }
+
+    /// Atomically adds `values` to a string set attribute and returns the whole set. A missing
+    /// item is created with just its key.
+    async fn add_to_set(
+        &self,
+        pk: &str,
+        sk: &str,
+        attribute: &str,
+        values: &[&str],
+    ) -> Result<HashSet<String>> {
+        let update = Update::new().add(attribute, string_set(values)?);
+        string_set_value(self.update_attribute(pk, sk, &update, attribute).await?)
+            .ok_or_else(|| unexpected_type(pk, sk, attribute, "a string set"))
+    }
+
+    /// Atomically removes `values` from a string set attribute and returns what is left; the
+    /// attribute is unset once it is empty.
+    async fn remove_from_set(
+        &self,
+        pk: &str,
+        sk: &str,
+        attribute: &str,
+        values: &[&str],
+    ) -> Result<HashSet<String>> {
+        let update = Update::new().delete(attribute, string_set(values)?);
+        string_set_value(self.update_attribute(pk, sk, &update, attribute).await?)
+            .ok_or_else(|| unexpected_type(pk, sk, attribute, "a string set"))
+    }
+
+    async fn query_index(&self, index_name: &str, query: &Query) -> Result<Vec<T>> {
+        self.query_all(&query.clone().index(index_name)).await
+    }
+
+    async fn get_entries_by_pk(&self, pk: &str) -> Result<Vec<T>> {
+        self.query_all(&Query::new(pk)).await
+    }
+}
+
+/// DynamoDB rejects empty sets, so an empty update is refused up front.
+fn string_set(values: &[&str]) -> Result<HashSet<String>> {
+    if values.is_empty() {
+        return Err(TableError::InvalidRequest("No set values given".into()));
+    }
+    Ok(values.iter().map(|value| (*value).to_owned()).collect())
+}
+
+/// An unset attribute is an empty set.
+fn string_set_value(value: Option<AttributeValue>) -> Option<HashSet<String>> {
+    match value {
+        Some(AttributeValue::Ss(values)) => Some(values.into_iter().collect()),
+        Some(_) => None,
+        None => Some(HashSet::new()),
+    }
+}
+
+fn unexpected_type(pk: &str, sk: &str, attribute: &str, expected: &str) -> TableError {
+    TableError::Service(format!("{attribute} on {pk}:{sk} is not {expected}").into())
 }
@@ -0,0 +1,255 @@
+use crate::table::codec;
+use crate::table::dynamo_db::OVERFLOW_ATTRIBUTE;
+use crate::table::error::{Result, TableError};
+use aws_sdk_dynamodb::types::AttributeValue;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// Holds attributes too large to store in the item itself, e.g. an S3 bucket.
+#[async_trait::async_trait]
+pub trait ObjectStore: Send + Sync {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> anyhow::Result<()>;
+    async fn get_object(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+
+    /// Deleting a missing object is not an error.
+    async fn delete_object(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// `ObjectStore` backed by a map, for tests and local runs.
+#[derive(Default)]
+pub struct InMemoryObjectStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryObjectStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.lock().map_or(0, |objects| objects.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for InMemoryObjectStore {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> anyhow::Result<()> {
+        self.objects
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Object store lock poisoned"))?
+            .insert(key.to_owned(), body);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        self.objects
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Object store lock poisoned"))?
+            .get(key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Object {key} not found"))
+    }
+
+    async fn delete_object(&self, key: &str) -> anyhow::Result<()> {
+        self.objects
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Object store lock poisoned"))?
+            .remove(key);
+        Ok(())
+    }
+}
+
+/// `ObjectStore` writing one file per object under a root directory.
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> anyhow::Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, body).await?;
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(tokio::fs::read(self.root.join(key)).await?)
+    }
+
+    async fn delete_object(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Moves the largest attributes not in `pinned` to the store until the item fits in `threshold`
+/// bytes, recording their object keys in the overflow attribute.
+pub(crate) async fn offload_attributes(
+    store: &dyn ObjectStore,
+    item: &mut Item,
+    pk: &str,
+    sk: &str,
+    threshold: usize,
+    pinned: &[&str],
+) -> Result<()> {
+    let mut sizes = Vec::new();
+    let mut total = 0;
+    for (name, value) in item.iter() {
+        let size = name.len() + encoded(value)?.len();
+        total += size;
+        if !pinned.contains(&name.as_str()) {
+            sizes.push((size, name.clone()));
+        }
+    }
+
+    if total <= threshold {
+        return Ok(());
+    }
+
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut pointers = HashMap::new();
+    for (size, name) in sizes {
+        if total <= threshold {
+            break;
+        }
+
+        let Some(value) = item.remove(&name) else {
+            continue;
+        };
+        let body = encoded(&value)?;
+        let key = object_key(pk, sk, &name, &body);
+        store
+            .put_object(&key, body)
+            .await
+            .map_err(|e| TableError::Overflow(e.into()))?;
+
+        total -= size;
+        total += name.len() + key.len();
+        pointers.insert(name, AttributeValue::S(key));
+    }
+
+    if total > threshold {
+        return Err(TableError::Overflow(
+            format!("Item {pk}:{sk} is still {total} bytes after offloading").into(),
+        ));
+    }
+
+    item.insert(OVERFLOW_ATTRIBUTE.to_owned(), AttributeValue::M(pointers));
+    Ok(())
+}
+
+/// Restores offloaded attributes, only those in `attributes` if given.
+///
+/// An attribute written since by `update_entry` is kept instead of the offloaded value.
+pub(crate) async fn rehydrate_attributes(
+    store: &dyn ObjectStore,
+    item: &mut Item,
+    attributes: Option<&[&str]>,
+) -> Result<()> {
+    for (name, key) in pointers(item.remove(OVERFLOW_ATTRIBUTE).as_ref())? {
+        let projected_out =
+            attributes.is_some_and(|attributes| !attributes.contains(&name.as_str()));
+        if projected_out || item.contains_key(&name) {
+            continue;
+        }
+
+        let body = store
+            .get_object(&key)
+            .await
+            .map_err(|e| TableError::Overflow(e.into()))?;
+        let value = codec::decode(&body).map_err(|e| TableError::Overflow(e.into()))?;
+        item.insert(name, value);
+    }
+
+    Ok(())
+}
+
+/// Deletes the objects referenced by `old` that `new` no longer points to.
+pub(crate) async fn delete_unreferenced(
+    store: &dyn ObjectStore,
+    old: &Item,
+    new: Option<&Item>,
+) -> Result<()> {
+    let kept = pointers(new.and_then(|new| new.get(OVERFLOW_ATTRIBUTE)))?
+        .into_iter()
+        .map(|(_, key)| key)
+        .collect::<Vec<_>>();
+
+    for (_, key) in pointers(old.get(OVERFLOW_ATTRIBUTE))? {
+        if !kept.contains(&key) {
+            store
+                .delete_object(&key)
+                .await
+                .map_err(|e| TableError::Overflow(e.into()))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn pointers(overflow: Option<&AttributeValue>) -> Result<Vec<(String, String)>> {
+    let Some(overflow) = overflow else {
+        return Ok(Vec::new());
+    };
+    let AttributeValue::M(pointers) = overflow else {
+        return Err(TableError::Overflow("Malformed overflow attribute".into()));
+    };
+
+    pointers
+        .iter()
+        .map(|(name, key)| match key {
+            AttributeValue::S(key) => Ok((name.clone(), key.clone())),
+            _ => Err(TableError::Overflow("Malformed overflow attribute".into())),
+        })
+        .collect()
+}
+
+fn encoded(value: &AttributeValue) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    codec::encode(value, &mut body).map_err(|e| TableError::Overflow(e.into()))?;
+    Ok(body)
+}
+
+/// Keys are unique to the item, attribute and content, so a failed conditional write never
+/// replaces an object the stored item still points to.
+fn object_key(pk: &str, sk: &str, attribute: &str, body: &[u8]) -> String {
+    format!(
+        "{}/{}/{}/{:x}",
+        escape(pk),
+        escape(sk),
+        escape(attribute),
+        Sha256::digest(body)
+    )
+}
+
+/// Percent-encodes everything but ASCII alphanumerics, `-` and `_`, so segments are safe as
+/// object keys and file names.
+fn escape(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
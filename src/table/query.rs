@@ -0,0 +1,147 @@
+use crate::table::expression::{Condition, Placeholders};
+use crate::table::schema::KeyAttribute;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKeyCondition {
+    Equals(String),
+    BeginsWith(String),
+    Between(String, String),
+    LessThan(String),
+    LessThanOrEqual(String),
+    GreaterThan(String),
+    GreaterThanOrEqual(String),
+}
+
+impl SortKeyCondition {
+    pub(crate) fn render(
+        &self,
+        sort_key: &KeyAttribute,
+        placeholders: &mut Placeholders,
+    ) -> String {
+        let name = placeholders.name(&sort_key.name);
+        let mut value = |value: &String| placeholders.value(&sort_key.attribute(value));
+
+        match self {
+            SortKeyCondition::Equals(v) => format!("{name} = {}", value(v)),
+            SortKeyCondition::BeginsWith(prefix) => {
+                format!("begins_with({name}, {})", value(prefix))
+            }
+            SortKeyCondition::Between(low, high) => {
+                format!("{name} BETWEEN {} AND {}", value(low), value(high))
+            }
+            SortKeyCondition::LessThan(v) => format!("{name} < {}", value(v)),
+            SortKeyCondition::LessThanOrEqual(v) => format!("{name} <= {}", value(v)),
+            SortKeyCondition::GreaterThan(v) => format!("{name} > {}", value(v)),
+            SortKeyCondition::GreaterThanOrEqual(v) => format!("{name} >= {}", value(v)),
+        }
+    }
+}
+
+/// Opaque position in a partition, used to resume a query where the previous page stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor(pub(crate) HashMap<String, AttributeValue>);
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub cursor: Option<Cursor>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub(crate) pk: String,
+    pub(crate) index: Option<String>,
+    pub(crate) sort_key: Option<SortKeyCondition>,
+    pub(crate) limit: Option<i32>,
+    pub(crate) scan_forward: bool,
+    pub(crate) cursor: Option<Cursor>,
+}
+
+impl Query {
+    pub fn new(pk: &str) -> Self {
+        Self {
+            pk: pk.to_owned(),
+            index: None,
+            sort_key: None,
+            limit: None,
+            scan_forward: true,
+            cursor: None,
+        }
+    }
+
+    /// Targets a secondary index registered on the table instead of the primary key.
+    pub fn index(mut self, index_name: &str) -> Self {
+        self.index = Some(index_name.to_owned());
+        self
+    }
+
+    pub fn sort_key(mut self, condition: SortKeyCondition) -> Self {
+        self.sort_key = Some(condition);
+        self
+    }
+
+    pub fn limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn scan_forward(mut self, scan_forward: bool) -> Self {
+        self.scan_forward = scan_forward;
+        self
+    }
+
+    pub fn start_from(mut self, cursor: Cursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+/// A full-table (or index) scan, split into `segments` that are read in parallel.
+#[derive(Debug, Clone)]
+pub struct Scan {
+    pub(crate) index: Option<String>,
+    pub(crate) filter: Option<Condition>,
+    pub(crate) segments: i32,
+    pub(crate) page_size: Option<i32>,
+}
+
+impl Default for Scan {
+    fn default() -> Self {
+        Self {
+            index: None,
+            filter: None,
+            segments: 1,
+            page_size: None,
+        }
+    }
+}
+
+impl Scan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn index(mut self, index_name: &str) -> Self {
+        self.index = Some(index_name.to_owned());
+        self
+    }
+
+    /// Applied server-side; filtered-out items still count towards consumed capacity.
+    pub fn filter(mut self, condition: Condition) -> Self {
+        self.filter = Some(condition);
+        self
+    }
+
+    pub fn segments(mut self, segments: i32) -> Self {
+        self.segments = segments.max(1);
+        self
+    }
+
+    /// Maximum number of items evaluated per request.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+}
@@ -0,0 +1,123 @@
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    String,
+    Number,
+    /// Stored as the UTF-8 bytes of the key string.
+    Binary,
+}
+
+impl KeyType {
+    pub(crate) fn attribute(&self, value: &str) -> AttributeValue {
+        match self {
+            KeyType::String => AttributeValue::S(value.to_owned()),
+            KeyType::Number => AttributeValue::N(value.to_owned()),
+            KeyType::Binary => AttributeValue::B(Blob::new(value.as_bytes())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAttribute {
+    pub(crate) name: String,
+    pub(crate) key_type: KeyType,
+}
+
+impl KeyAttribute {
+    pub fn new(name: &str, key_type: KeyType) -> Self {
+        Self {
+            name: name.to_owned(),
+            key_type,
+        }
+    }
+
+    pub fn string(name: &str) -> Self {
+        Self::new(name, KeyType::String)
+    }
+
+    pub fn number(name: &str) -> Self {
+        Self::new(name, KeyType::Number)
+    }
+
+    pub fn binary(name: &str) -> Self {
+        Self::new(name, KeyType::Binary)
+    }
+
+    pub(crate) fn attribute(&self, value: &str) -> AttributeValue {
+        self.key_type.attribute(value)
+    }
+}
+
+/// Names and types of the attributes that make up a table or index key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySchema {
+    pub(crate) partition_key: KeyAttribute,
+    pub(crate) sort_key: Option<KeyAttribute>,
+}
+
+impl KeySchema {
+    pub fn new(partition_key: KeyAttribute) -> Self {
+        Self {
+            partition_key,
+            sort_key: None,
+        }
+    }
+
+    pub fn sort_key(mut self, sort_key: KeyAttribute) -> Self {
+        self.sort_key = Some(sort_key);
+        self
+    }
+}
+
+impl Default for KeySchema {
+    fn default() -> Self {
+        Self::new(KeyAttribute::string("PK")).sort_key(KeyAttribute::string("SK"))
+    }
+}
+
+/// A global or local secondary index and the attributes that key it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    pub(crate) name: String,
+    pub(crate) key_schema: KeySchema,
+}
+
+impl Index {
+    pub fn new(name: &str, partition_key: &str) -> Self {
+        Self::with_schema(name, KeySchema::new(KeyAttribute::string(partition_key)))
+    }
+
+    pub fn with_schema(name: &str, key_schema: KeySchema) -> Self {
+        Self {
+            name: name.to_owned(),
+            key_schema,
+        }
+    }
+
+    pub fn sort_key(mut self, sort_key: &str) -> Self {
+        self.key_schema.sort_key = Some(KeyAttribute::string(sort_key));
+        self
+    }
+}
+
+/// Attribute names written by `DynamoDbClient::put_tracked`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackingAttributes {
+    pub created_at: String,
+    pub updated_at: String,
+    pub expires_at: String,
+    pub version: String,
+}
+
+impl Default for TrackingAttributes {
+    fn default() -> Self {
+        Self {
+            created_at: "created_at".to_owned(),
+            updated_at: "updated_at".to_owned(),
+            expires_at: "expires_at".to_owned(),
+            version: "version".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use crate::Table;
+use crate::model::Keyed;
+use anyhow::{Result, anyhow};
+use aws_sdk_dynamodbstreams::Client;
+use aws_sdk_dynamodbstreams::error::SdkError;
+use aws_sdk_dynamodbstreams::operation::get_records::{GetRecordsError, GetRecordsOutput};
+use aws_sdk_dynamodbstreams::types::{AttributeValue, OperationType, Record, ShardIteratorType};
+use futures::Stream;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+const DEFAULT_ENTITY_TYPE_ATTRIBUTE: &str = "EntityType";
+
+type Image = HashMap<String, AttributeValue>;
+
+/// A change to an item; images are `None` when the stream view type does not include them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent<T> {
+    Insert { new: Option<T> },
+    Modify { old: Option<T>, new: Option<T> },
+    Remove { old: Option<T> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEvent<T> {
+    pub shard_id: String,
+    pub sequence_number: String,
+    pub change: ChangeEvent<T>,
+}
+
+/// Last processed position in a shard, stored through any `Table<ShardCheckpoint>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardCheckpoint {
+    pub stream_arn: String,
+    pub shard_id: String,
+    pub sequence_number: String,
+}
+
+impl Keyed for ShardCheckpoint {
+    fn pk(&self) -> String {
+        checkpoint_pk(&self.stream_arn)
+    }
+
+    fn sk(&self) -> String {
+        self.shard_id.clone()
+    }
+}
+
+fn checkpoint_pk(stream_arn: &str) -> String {
+    format!("CHECKPOINT#{stream_arn}")
+}
+
+#[derive(Debug, Default, Clone)]
+struct ShardPosition {
+    iterator: Option<String>,
+    sequence_number: Option<String>,
+    finished: bool,
+}
+
+/// Reads a DynamoDB stream shard by shard, yielding typed changes.
+///
+/// Positions advance in memory as records are read; call `checkpoint` once an event has been
+/// handled so a restarted consumer resumes after it (at-least-once delivery).
+pub struct StreamConsumer<T> {
+    client: Client,
+    stream_arn: String,
+    checkpoints: Arc<dyn Table<ShardCheckpoint>>,
+    entity_type_attribute: String,
+    positions: Mutex<Option<HashMap<String, ShardPosition>>>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T> StreamConsumer<T>
+where
+    T: DeserializeOwned + Keyed,
+{
+    pub fn builder() -> StreamConsumerBuilder<T> {
+        StreamConsumerBuilder::default()
+    }
+
+    /// Reads the next batch of records from every open shard.
+    pub async fn poll(&self) -> Result<Vec<StreamEvent<T>>> {
+        let cached = self.positions()?.clone();
+        let mut positions = match cached {
+            Some(positions) => positions,
+            None => self.load_checkpoints().await?,
+        };
+
+        let mut events = Vec::new();
+        for shard_id in self.shard_ids().await? {
+            let position = positions.entry(shard_id.clone()).or_default();
+            if position.finished {
+                continue;
+            }
+
+            let resp = self.get_records(&shard_id, position).await?;
+            position.iterator = resp.next_shard_iterator().map(str::to_owned);
+            position.finished = position.iterator.is_none();
+
+            for record in resp.records() {
+                let Some(sequence_number) = record
+                    .dynamodb()
+                    .and_then(|stream_record| stream_record.sequence_number())
+                else {
+                    continue;
+                };
+                position.sequence_number = Some(sequence_number.to_owned());
+
+                if let Some(change) = self.change_event(record)? {
+                    events.push(StreamEvent {
+                        shard_id: shard_id.clone(),
+                        sequence_number: sequence_number.to_owned(),
+                        change,
+                    });
+                }
+            }
+        }
+
+        *self.positions()? = Some(positions);
+        Ok(events)
+    }
+
+    /// Persists `event` as the last processed record of its shard.
+    pub async fn checkpoint(&self, event: &StreamEvent<T>) -> Result<()> {
+        self.checkpoints
+            .put_entry(ShardCheckpoint {
+                stream_arn: self.stream_arn.clone(),
+                shard_id: event.shard_id.clone(),
+                sequence_number: event.sequence_number.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Polls continuously, waiting `poll_interval` whenever the stream has no new records.
+    pub fn events(
+        &self,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<StreamEvent<T>>> + '_ {
+        futures::stream::unfold(VecDeque::new(), move |mut buffer| async move {
+            loop {
+                if let Some(event) = buffer.pop_front() {
+                    return Some((Ok(event), buffer));
+                }
+
+                match self.poll().await {
+                    Ok(events) if events.is_empty() => tokio::time::sleep(poll_interval).await,
+                    Ok(events) => buffer.extend(events),
+                    Err(e) => return Some((Err(e), buffer)),
+                }
+            }
+        })
+    }
+
+    fn positions(&self) -> Result<MutexGuard<'_, Option<HashMap<String, ShardPosition>>>> {
+        self.positions
+            .lock()
+            .map_err(|_| anyhow!("Stream consumer lock poisoned"))
+    }
+
+    async fn load_checkpoints(&self) -> Result<HashMap<String, ShardPosition>> {
+        let checkpoints = self
+            .checkpoints
+            .get_entries_by_pk(&checkpoint_pk(&self.stream_arn))
+            .await?;
+
+        Ok(checkpoints
+            .into_iter()
+            .map(|checkpoint| {
+                let position = ShardPosition {
+                    sequence_number: Some(checkpoint.sequence_number),
+                    ..ShardPosition::default()
+                };
+                (checkpoint.shard_id, position)
+            })
+            .collect())
+    }
+
+    /// Lists shards with parents ahead of their children, so changes to a key stay in order.
+    async fn shard_ids(&self) -> Result<Vec<String>> {
+        let mut shards = Vec::new();
+        let mut exclusive_start_shard_id = None;
+
+        loop {
+            let resp = self
+                .client
+                .describe_stream()
+                .stream_arn(&self.stream_arn)
+                .set_exclusive_start_shard_id(exclusive_start_shard_id)
+                .send()
+                .await?;
+
+            let description = resp
+                .stream_description
+                .ok_or_else(|| anyhow!("No description for stream {}", self.stream_arn))?;
+            shards.extend(description.shards().iter().filter_map(|shard| {
+                Some((
+                    shard.shard_id()?.to_owned(),
+                    shard.parent_shard_id().map(str::to_owned),
+                ))
+            }));
+
+            exclusive_start_shard_id = description.last_evaluated_shard_id;
+            if exclusive_start_shard_id.is_none() {
+                break;
+            }
+        }
+
+        let mut ordered: Vec<String> = Vec::with_capacity(shards.len());
+        while !shards.is_empty() {
+            let known = |id: &String| shards.iter().any(|(shard_id, _)| shard_id == id);
+            let (ready, waiting): (Vec<_>, Vec<_>) = shards
+                .iter()
+                .cloned()
+                .partition(|(_, parent)| parent.as_ref().is_none_or(|parent| !known(parent)));
+
+            if ready.is_empty() {
+                ordered.extend(waiting.into_iter().map(|(shard_id, _)| shard_id));
+                break;
+            }
+            ordered.extend(ready.into_iter().map(|(shard_id, _)| shard_id));
+            shards = waiting;
+        }
+
+        Ok(ordered)
+    }
+
+    async fn get_records(
+        &self,
+        shard_id: &str,
+        position: &ShardPosition,
+    ) -> Result<GetRecordsOutput> {
+        if let Some(iterator) = &position.iterator {
+            match self
+                .client
+                .get_records()
+                .shard_iterator(iterator)
+                .send()
+                .await
+            {
+                Err(SdkError::ServiceError(e))
+                    if matches!(e.err(), GetRecordsError::ExpiredIteratorException(_)) => {}
+                result => return Ok(result?),
+            }
+        }
+
+        let iterator = self.shard_iterator(shard_id, position).await?;
+        Ok(self
+            .client
+            .get_records()
+            .shard_iterator(iterator)
+            .send()
+            .await?)
+    }
+
+    async fn shard_iterator(&self, shard_id: &str, position: &ShardPosition) -> Result<String> {
+        let iterator_type = match position.sequence_number {
+            Some(_) => ShardIteratorType::AfterSequenceNumber,
+            None => ShardIteratorType::TrimHorizon,
+        };
+
+        self.client
+            .get_shard_iterator()
+            .stream_arn(&self.stream_arn)
+            .shard_id(shard_id)
+            .shard_iterator_type(iterator_type)
+            .set_sequence_number(position.sequence_number.clone())
+            .send()
+            .await?
+            .shard_iterator
+            .ok_or_else(|| anyhow!("No iterator returned for shard {}", shard_id))
+    }
+
+    fn change_event(&self, record: &Record) -> Result<Option<ChangeEvent<T>>> {
+        let Some(stream_record) = record.dynamodb() else {
+            return Ok(None);
+        };
+        let (old, new) = (stream_record.old_image(), stream_record.new_image());
+
+        if !self.is_entity(old.or(new)) {
+            return Ok(None);
+        }
+
+        let old = old.cloned().map(serde_dynamo::from_item).transpose()?;
+        let new = new.cloned().map(serde_dynamo::from_item).transpose()?;
+
+        let change = match record.event_name() {
+            Some(OperationType::Insert) => ChangeEvent::Insert { new },
+            Some(OperationType::Modify) => ChangeEvent::Modify { old, new },
+            Some(OperationType::Remove) => ChangeEvent::Remove { old },
+            _ => return Ok(None),
+        };
+        Ok(Some(change))
+    }
+
+    fn is_entity(&self, image: Option<&Image>) -> bool {
+        let (Some(entity_type), Some(image)) = (T::entity_type(), image) else {
+            return true;
+        };
+
+        matches!(
+            image.get(&self.entity_type_attribute),
+            Some(AttributeValue::S(value)) if value == entity_type
+        )
+    }
+}
+
+pub struct StreamConsumerBuilder<T> {
+    client: Option<Client>,
+    stream_arn: Option<String>,
+    checkpoints: Option<Arc<dyn Table<ShardCheckpoint>>>,
+    entity_type_attribute: Option<String>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for StreamConsumerBuilder<T> {
+    fn default() -> Self {
+        Self {
+            client: None,
+            stream_arn: None,
+            checkpoints: None,
+            entity_type_attribute: None,
+            _entry: PhantomData,
+        }
+    }
+}
+
+impl<T> StreamConsumerBuilder<T> {
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn stream_arn(mut self, stream_arn: &str) -> Self {
+        self.stream_arn = Some(stream_arn.to_owned());
+        self
+    }
+
+    pub fn checkpoints(mut self, checkpoints: Arc<dyn Table<ShardCheckpoint>>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Must match the `DynamoDbClient` writing the table, `EntityType` by default.
+    pub fn entity_type_attribute(mut self, attribute: &str) -> Self {
+        self.entity_type_attribute = Some(attribute.to_owned());
+        self
+    }
+
+    pub async fn build(self) -> Result<StreamConsumer<T>> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let config = aws_config::load_from_env().await;
+                Client::new(&config)
+            }
+        };
+
+        let stream_arn = self.stream_arn.ok_or(anyhow!("Missing stream arn"))?;
+        let checkpoints = self
+            .checkpoints
+            .ok_or(anyhow!("Missing checkpoint table"))?;
+
+        Ok(StreamConsumer {
+            client,
+            stream_arn,
+            checkpoints,
+            entity_type_attribute: self
+                .entity_type_attribute
+                .unwrap_or_else(|| DEFAULT_ENTITY_TYPE_ATTRIBUTE.to_owned()),
+            positions: Mutex::new(None),
+            _entry: PhantomData,
+        })
+    }
+}